@@ -0,0 +1,205 @@
+use darling::{FromDeriveInput, ToTokens};
+use quote::{format_ident, quote};
+
+use crate::util::{FieldExt, StructExt};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(forward_attrs(allow, doc, cfg))]
+#[darling(attributes(builder), supports(struct_named))]
+pub(super) struct EntityBuilder {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    generics: syn::Generics,
+    data: darling::ast::Data<(), syn::Field>,
+    #[darling(default)]
+    key_generator: Option<syn::Path>,
+}
+
+impl ToTokens for EntityBuilder {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let EntityBuilder {
+            ref ident,
+            ref vis,
+            ref generics,
+            ref data,
+            ref key_generator,
+        } = *self;
+
+        if !generics.params.is_empty() {
+            panic!("generic entities are not supported by `EntityBuilder`");
+        }
+
+        let builder_ident = format_ident!("{}Builder", ident);
+
+        let id_field = data
+            .get_field_by_attr_or_id("id_field", "id")
+            .expect("`id` field is required");
+        let created_at_field = data
+            .get_field_by_attr_or_id("created_at_field", "created_at")
+            .expect("`created_at` field is required");
+        let updated_at_field =
+            data.get_field_by_attr_or_id("updated_at_field", "updated_at");
+        let deleted_at_field =
+            data.get_field_by_attr_or_id("deleted_at_field", "deleted_at");
+
+        let (id_ident, id_ty) = (&id_field.ident, &id_field.ty);
+        let created_at_ident = &created_at_field.ident;
+
+        let auto_fields = {
+            let mut fields = vec![quote!(#created_at_ident: now,)];
+
+            if let Some(f) = updated_at_field {
+                let id = &f.ident;
+
+                fields.push(quote!(#id: now,));
+            }
+
+            if let Some(f) = deleted_at_field {
+                let id = &f.ident;
+
+                fields.push(quote!(#id: None,));
+            }
+
+            fields
+        };
+
+        let auto_idents: Vec<_> = [
+            Some(id_field),
+            Some(created_at_field),
+            updated_at_field,
+            deleted_at_field,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f.id())
+        .collect();
+
+        let fields: Vec<_> = data
+            .get_fields()
+            .into_iter()
+            .filter(|f| !auto_idents.contains(f.ident.as_ref().unwrap()))
+            .map(|f| (f.ident.as_ref().unwrap(), &f.ty))
+            .collect();
+
+        let states: Vec<_> = (0..fields.len())
+            .map(|i| format_ident!("__S{}", i))
+            .collect();
+        let idents: Vec<_> = fields.iter().map(|(id, _)| *id).collect();
+        let tys: Vec<_> = fields.iter().map(|(_, ty)| *ty).collect();
+
+        let setters = fields.iter().enumerate().map(|(i, (id, ty))| {
+            let name = id.to_string();
+            let others: Vec<_> = states
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, s)| s)
+                .collect();
+            let unset_states = states.iter().enumerate().map(|(j, s)| {
+                if i == j {
+                    quote!(Unset)
+                } else {
+                    quote!(#s)
+                }
+            });
+            let set_states = states.iter().enumerate().map(|(j, s)| {
+                if i == j {
+                    quote!(Set<#ty>)
+                } else {
+                    quote!(#s)
+                }
+            });
+            let moved = idents.iter().map(|other| {
+                if other == id {
+                    quote!(#other: Set::convert(#name, value),)
+                } else {
+                    quote!(#other: self.#other,)
+                }
+            });
+
+            quote! {
+                impl<#(#others),*> #builder_ident<#(#unset_states),*> {
+                    #[doc = concat!("Sets the `", #name, "` field.")]
+                    pub fn #id<V>(
+                        self,
+                        value: V,
+                    ) -> #builder_ident<#(#set_states),*>
+                    where
+                        V: TryInto<#ty>,
+                        V::Error: std::fmt::Display,
+                    {
+                        #builder_ident {
+                            #(#moved)*
+                        }
+                    }
+                }
+            }
+        });
+
+        let build = key_generator.as_ref().map(|generator| {
+            quote! {
+                /// Builds the entity, generating its key with the key
+                /// generator of the entity and timestamping it with
+                /// [`SystemClock`].
+                pub fn build(self) -> Result<#ident, BuildError> {
+                    self.build_with(&#generator, &SystemClock)
+                }
+            }
+        });
+
+        let builder_doc = format!(
+            "A type-state builder of [`{ident}`], which can only be built \
+             once all fields are set."
+        );
+
+        tokens.extend(quote! {
+            #[doc = #builder_doc]
+            #vis struct #builder_ident<#(#states = Unset),*> {
+                #(#idents: #states,)*
+            }
+
+            impl #ident {
+                #[doc = concat!("Creates a new [`", stringify!(#builder_ident), "`].")]
+                #vis fn builder() -> #builder_ident {
+                    #builder_ident {
+                        #(#idents: Unset,)*
+                    }
+                }
+            }
+
+            #(#setters)*
+
+            impl #builder_ident<#(Set<#tys>),*> {
+                /// Builds the entity, generating its key with `keys` and
+                /// timestamping it with `clock`, then checks its invariants.
+                ///
+                /// # Arguments
+                ///
+                /// * `keys` - The generator to generate the key with.
+                /// * `clock` - The clock to timestamp the entity with.
+                pub fn build_with<G, C>(
+                    self,
+                    keys: &G,
+                    clock: &C,
+                ) -> Result<#ident, BuildError>
+                where
+                    G: KeyGenerator<#id_ty>,
+                    C: Clock,
+                {
+                    let now = clock.now();
+                    let entity = #ident {
+                        #id_ident: keys.generate(),
+                        #(#auto_fields)*
+                        #(#idents: self.#idents.into_inner()?,)*
+                    };
+
+                    entity.check_invariants()?;
+
+                    Ok(entity)
+                }
+
+                #build
+            }
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use darling::{FromDeriveInput, FromField, ToTokens};
+use quote::{format_ident, quote};
+
+use crate::util::used_generics;
+
+#[derive(Debug, FromField)]
+#[darling(attributes(diff))]
+struct DiffField {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+    #[darling(default)]
+    skip: bool,
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(diff), forward_attrs(allow, doc, cfg))]
+#[darling(supports(struct_named))]
+pub(super) struct Diff {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    generics: syn::Generics,
+    data: darling::ast::Data<(), DiffField>,
+    #[darling(default)]
+    name: Option<syn::Ident>,
+    #[darling(default)]
+    serde: bool,
+}
+
+impl ToTokens for Diff {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Diff {
+            ref ident,
+            ref vis,
+            ref generics,
+            ref data,
+            ref name,
+            serde,
+        } = *self;
+
+        let (imp, ty, wher) = generics.split_for_impl();
+
+        let changes_ident = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Changes", ident));
+
+        let fields: Vec<_> = data
+            .as_ref()
+            .take_struct()
+            .expect("only structs are supported")
+            .fields
+            .into_iter()
+            .filter(|f| !f.skip)
+            .map(|f| (f.ident.as_ref().unwrap(), &f.ty))
+            .collect();
+
+        // the change-set only gets the generics its fields use, as unused
+        // ones are rejected by the compiler
+        let changes_generics = used_generics(
+            generics,
+            &fields.iter().map(|(_, ty)| ty.to_token_stream()).collect(),
+        );
+        let (changes_imp, changes_ty, changes_wher) =
+            changes_generics.split_for_impl();
+
+        let (serde_derive, serde_field) = if serde {
+            (
+                quote!(#[derive(serde::Serialize, serde::Deserialize)]),
+                quote!(#[serde(default, skip_serializing_if = "Option::is_none")]),
+            )
+        } else {
+            (quote!(), quote!())
+        };
+
+        let doc = format!(
+            "A change-set of [`{ident}`], which holds the changed fields only."
+        );
+
+        let changes_fields = fields.iter().map(|(id, ty)| {
+            quote! {
+                #serde_field
+                pub #id: Option<FieldChange<#ty>>,
+            }
+        });
+
+        let diff_fields = fields.iter().map(|(id, _)| {
+            quote! {
+                #id: (self.#id != other.#id).then(|| FieldChange {
+                    old: self.#id.clone(),
+                    new: other.#id.clone(),
+                }),
+            }
+        });
+
+        let changed_fields = fields.iter().map(|(id, _)| {
+            let name = id.to_string();
+
+            quote! {
+                if self.#id.is_some() {
+                    fields.push(#name);
+                }
+            }
+        });
+
+        tokens.extend(quote! {
+            #[doc = #doc]
+            #[derive(Clone, Debug, Default, PartialEq)]
+            #serde_derive
+            #vis struct #changes_ident #changes_generics #changes_wher {
+                #(#changes_fields)*
+            }
+
+            impl #changes_imp ChangeSet for #changes_ident #changes_ty
+            #changes_wher
+            {
+                fn changed_fields(&self) -> Vec<&'static str> {
+                    let mut fields = Vec::new();
+
+                    #(#changed_fields)*
+
+                    fields
+                }
+            }
+
+            impl #imp Diff for #ident #ty #wher {
+                type Changes = #changes_ident #changes_ty;
+
+                fn diff(&self, other: &Self) -> Self::Changes {
+                    #changes_ident {
+                        #(#diff_fields)*
+                    }
+                }
+            }
+        });
+    }
+}
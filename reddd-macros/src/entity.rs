@@ -1,26 +1,218 @@
-use darling::{FromDeriveInput, ToTokens};
+use darling::{
+    ast::Data,
+    util::Ignored,
+    FromDeriveInput,
+    FromVariant,
+    ToTokens,
+};
+use quote::quote;
 
-use crate::util::StructExt;
+use crate::util::FieldExt;
+
+#[derive(Debug, FromVariant)]
+pub(super) struct EntityVariant {
+    ident: syn::Ident,
+    fields: darling::ast::Fields<syn::Field>,
+}
 
 #[derive(Debug, FromDeriveInput)]
 #[darling(forward_attrs(allow, doc, cfg))]
-#[darling(attributes(id_field, created_at_field), supports(struct_named))]
+#[darling(
+    attributes(id_field, created_at_field, entity),
+    supports(struct_named, struct_tuple, enum_named, enum_tuple)
+)]
 pub(super) struct Entity {
     ident: syn::Ident,
     generics: syn::Generics,
-    data: darling::ast::Data<(), syn::Field>,
+    data: Data<EntityVariant, syn::Field>,
+    #[darling(default)]
+    id: Option<String>,
+    #[darling(default)]
+    key_type: Option<syn::Type>,
+    #[darling(default)]
+    created_at: Option<String>,
+    #[darling(default, multiple, rename = "invariant")]
+    invariants: Vec<syn::Path>,
+    // options of `MutableEntity`, which shares the `entity` attribute
+    #[darling(default, rename = "updated_at")]
+    _updated_at: Option<Ignored>,
+    #[darling(default, rename = "deleted_at")]
+    _deleted_at: Option<Ignored>,
 }
 
 #[derive(Debug, FromDeriveInput)]
 #[darling(forward_attrs(allow, doc, cfg))]
 #[darling(
-    attributes(id_field, created_at_field, updated_at_field),
-    supports(struct_named)
+    attributes(
+        id_field,
+        created_at_field,
+        updated_at_field,
+        deleted_at_field,
+        created_by_field,
+        updated_by_field,
+        entity
+    ),
+    supports(struct_named, struct_tuple, enum_named, enum_tuple)
 )]
 pub(super) struct MutableEntity {
     ident: syn::Ident,
     generics: syn::Generics,
-    data: darling::ast::Data<(), syn::Field>,
+    data: Data<EntityVariant, syn::Field>,
+    #[darling(default)]
+    updated_at: Option<String>,
+    #[darling(default)]
+    deleted_at: Option<String>,
+    // options of `Entity`, which shares the `entity` attribute
+    #[darling(default, rename = "id")]
+    _id: Option<Ignored>,
+    #[darling(default, rename = "key_type")]
+    _key_type: Option<Ignored>,
+    #[darling(default, rename = "created_at")]
+    _created_at: Option<Ignored>,
+    #[darling(default, multiple, rename = "invariant")]
+    _invariants: Vec<Ignored>,
+}
+
+/// Gets the fields of every variant of an entity, where structs are treated
+/// as a single unnamed variant.
+fn get_variants(
+    data: &Data<EntityVariant, syn::Field>,
+) -> Vec<(Option<&syn::Ident>, Vec<&syn::Field>)> {
+    let variants: Vec<_> = match data {
+        | Data::Struct(fields) => vec![(None, fields.iter().collect())],
+        | Data::Enum(variants) => variants
+            .iter()
+            .map(|v| (Some(&v.ident), v.fields.iter().collect()))
+            .collect(),
+    };
+
+    if variants.is_empty() {
+        panic!("enums without variants are not supported");
+    }
+
+    variants
+}
+
+/// Gets the member to access the field at `index` with.
+fn get_member(index: usize, field: &syn::Field) -> syn::Member {
+    match field.ident {
+        | Some(ref ident) => syn::Member::Named(ident.clone()),
+        | None => syn::Member::Unnamed(index.into()),
+    }
+}
+
+/// Finds the field annotated with `attr`, or named `name` if given, and
+/// returns its member along with its type.
+fn find_field<'a>(
+    fields: &[&'a syn::Field],
+    attr: &str,
+    name: Option<&str>,
+) -> Option<(syn::Member, &'a syn::Type)> {
+    let annotated: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.has_attribute(attr))
+        .collect();
+
+    if annotated.len() > 1 && attr == "id_field" {
+        panic!(
+            "only one field can be annotated with #[id_field], as keys are \
+             borrowed from entities; composite keys must be kept in a single \
+             tuple or struct field"
+        );
+    }
+
+    if annotated.len() > 1 {
+        panic!("only one field can be annotated with #[{attr}]");
+    }
+
+    let (index, field) = annotated.first().copied().or_else(|| {
+        fields
+            .iter()
+            .enumerate()
+            .find(|(_, f)| match (&f.ident, name) {
+                | (Some(ident), Some(name)) => ident == name,
+                | _ => false,
+            })
+    })?;
+
+    Some((get_member(index, field), &field.ty))
+}
+
+/// Resolves a place expression of an entity field, along with the type of
+/// the field if it is a direct one.
+///
+/// The field is either referred to by a dotted `path` (e.g. `meta.id`), or
+/// annotated with `attr`, or named `name` if given. For enums, the field is
+/// looked up in every variant, and accessed through a `match` on `self`.
+fn resolve_field<'a>(
+    data: &'a Data<EntityVariant, syn::Field>,
+    path: Option<&str>,
+    attr: &str,
+    name: Option<&str>,
+) -> Option<(proc_macro2::TokenStream, Option<&'a syn::Type>)> {
+    let path: Vec<syn::Member> = path
+        .map(|path| {
+            path.split('.')
+                .map(|m| {
+                    syn::parse_str(m.trim()).unwrap_or_else(|_| {
+                        panic!("invalid field path `{path}`")
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let variants: Vec<_> = get_variants(data)
+        .into_iter()
+        .map(|(variant, fields)| {
+            let field = match path.split_first() {
+                | Some((first, [])) => Some((
+                    first.clone(),
+                    fields
+                        .iter()
+                        .enumerate()
+                        .find(|(i, f)| first == &get_member(*i, f))
+                        .map(|(_, f)| &f.ty),
+                )),
+                | Some((first, _)) => Some((first.clone(), None)),
+                | None => find_field(&fields, attr, name)
+                    .map(|(member, ty)| (member, Some(ty))),
+            };
+
+            (variant, field)
+        })
+        .collect();
+
+    if variants.iter().all(|(_, field)| field.is_none()) {
+        return None;
+    }
+
+    if let Some((Some(variant), _)) =
+        variants.iter().find(|(_, field)| field.is_none())
+    {
+        panic!(
+            "variant `{variant}` is missing the field annotated with \
+             #[{attr}], which all variants must have"
+        );
+    }
+
+    let rest = path.iter().skip(1);
+    let ty = variants.first().and_then(|(_, field)| field.as_ref()?.1);
+    let place = match variants.as_slice() {
+        | [(None, Some((member, _)))] => quote!(self.#member #(.#rest)*),
+        | _ => {
+            let arms = variants.iter().map(|(variant, field)| {
+                let (member, _) = field.as_ref().unwrap();
+
+                quote!(Self::#variant { #member: __field, .. } => __field,)
+            });
+
+            quote!((*match self { #(#arms)* }) #(.#rest)*)
+        }
+    };
+
+    Some((place, ty))
 }
 
 impl ToTokens for Entity {
@@ -29,32 +221,63 @@ impl ToTokens for Entity {
             ref ident,
             ref generics,
             ref data,
+            ref id,
+            ref key_type,
+            ref created_at,
+            ref invariants,
+            ..
         } = *self;
 
         let (imp, ty, wher) = generics.split_for_impl();
 
-        let id_field = data
-            .get_field_by_attr_or_id("id_field", "id")
-            .expect("`id` field is required");
+        let (id_place, id_ty) =
+            resolve_field(data, id.as_deref(), "id_field", Some("id"))
+                .expect("`id` field is required");
+
+        let id_ty = key_type.as_ref().or(id_ty).expect(
+            "`key_type` is required when the `id` field is a nested one",
+        );
+
+        let (created_at_place, _) = resolve_field(
+            data,
+            created_at.as_deref(),
+            "created_at_field",
+            Some("created_at"),
+        )
+        .expect("`created_at` field is required");
 
-        let created_at_field = data
-            .get_field_by_attr_or_id("created_at_field", "created_at")
-            .expect("`created_at` field is required");
+        let check_invariants = (!invariants.is_empty()).then(|| {
+            let entity = ident.to_string();
+            let names = invariants
+                .iter()
+                .map(|p| p.to_token_stream().to_string().replace(' ', ""));
 
-        let (id_ident, id_ty) = (id_field.ident.clone().unwrap(), &id_field.ty);
-        let created_at_ident = &created_at_field.ident.clone().unwrap();
+            quote! {
+                fn check_invariants(&self) -> Result<(), InvariantViolation> {
+                    #(
+                        #invariants(self).map_err(|reason| {
+                            InvariantViolation::new(#entity, #names, reason)
+                        })?;
+                    )*
 
-        tokens.extend(quote::quote! {
+                    Ok(())
+                }
+            }
+        });
+
+        tokens.extend(quote! {
             impl #imp Entity for #ident #ty #wher {
                 type Key = #id_ty;
 
                 fn id(&self) -> &Self::Key {
-                    &self.#id_ident
+                    &#id_place
                 }
 
                 fn created_at(&self) -> &DateTime<Utc> {
-                    &self.#created_at_ident
+                    &#created_at_place
                 }
+
+                #check_invariants
             }
         });
     }
@@ -66,27 +289,95 @@ impl ToTokens for MutableEntity {
             ref ident,
             ref generics,
             ref data,
+            ref updated_at,
+            ref deleted_at,
+            ..
         } = *self;
 
         let (imp, ty, wher) = generics.split_for_impl();
 
-        let updated_at_ident = &data
-            .get_field_by_attr_or_id("updated_at_field", "updated_at")
-            .expect("`updated_at` field is required")
-            .ident;
+        let (updated_at_place, _) = resolve_field(
+            data,
+            updated_at.as_deref(),
+            "updated_at_field",
+            Some("updated_at"),
+        )
+        .expect("`updated_at` field is required");
 
-        tokens.extend(quote::quote! {
+        tokens.extend(quote! {
             impl #imp MutableEntity for #ident #ty #wher {
                 fn updated_at(&self) -> &DateTime<Utc> {
-                    &self.#updated_at_ident
+                    &#updated_at_place
                 }
 
                 fn touch(&mut self) -> &DateTime<Utc> {
-                    self.#updated_at_ident = Utc::now();
+                    #updated_at_place = Utc::now();
 
                     &self.updated_at()
                 }
             }
         });
+
+        if let Some((deleted_at_place, _)) = resolve_field(
+            data,
+            deleted_at.as_deref(),
+            "deleted_at_field",
+            None,
+        ) {
+            tokens.extend(quote! {
+                impl #imp SoftDeletableEntity for #ident #ty #wher {
+                    fn deleted_at(&self) -> Option<&DateTime<Utc>> {
+                        #deleted_at_place.as_ref()
+                    }
+
+                    fn mark_deleted(&mut self) -> &DateTime<Utc> {
+                        let timestamp = *self.touch();
+
+                        #deleted_at_place.insert(timestamp)
+                    }
+
+                    fn mark_restored(&mut self) {
+                        #deleted_at_place = None;
+                        self.touch();
+                    }
+                }
+            });
+        }
+
+        match (
+            resolve_field(data, None, "created_by_field", None),
+            resolve_field(data, None, "updated_by_field", None),
+        ) {
+            | (
+                Some((created_by_place, actor_ty)),
+                Some((updated_by_place, _)),
+            ) => {
+                tokens.extend(quote! {
+                    impl #imp AuditedEntity for #ident #ty #wher {
+                        type Actor = #actor_ty;
+
+                        fn created_by(&self) -> &Self::Actor {
+                            &#created_by_place
+                        }
+
+                        fn updated_by(&self) -> &Self::Actor {
+                            &#updated_by_place
+                        }
+
+                        fn touch_by(
+                            &mut self,
+                            actor: Self::Actor,
+                        ) -> &DateTime<Utc> {
+                            #updated_by_place = actor;
+                            self.touch()
+                        }
+                    }
+                });
+            }
+            | (None, None) => {}
+            | _ => panic!(
+                "both #[created_by_field] and #[updated_by_field] are required"
+            ),
+        }
     }
 }
@@ -1,9 +1,12 @@
 use darling::{FromDeriveInput, ToTokens};
 use proc_macro::TokenStream;
 
+mod builder;
+mod diff;
 mod entity;
 mod usecase;
 mod util;
+mod value_object;
 mod value_type;
 
 macro_rules! parse_derive_input {
@@ -49,13 +52,30 @@ macro_rules! concat_token_streams {
     }}
 }
 
-define_proc_macro!(ValueType[main_field]);
-define_proc_macro!(Entity [id_field, created_at_field]);
+define_proc_macro!(ValueType [main_field, value_type]);
+define_proc_macro!(ValueObject [value_object]);
+define_proc_macro!(Entity [id_field, created_at_field, entity]);
 define_proc_macro!(UseCase with usecase::UseCase [usecase]);
+define_proc_macro!(Diff [diff]);
+define_proc_macro!(EntityBuilder with builder::EntityBuilder [
+    builder,
+    id_field,
+    created_at_field,
+    updated_at_field,
+    deleted_at_field
+]);
 
 #[proc_macro_derive(
     MutableEntity,
-    attributes(id_field, created_at_field, updated_at_field)
+    attributes(
+        id_field,
+        created_at_field,
+        updated_at_field,
+        deleted_at_field,
+        created_by_field,
+        updated_by_field,
+        entity
+    )
 )]
 #[proc_macro_error::proc_macro_error]
 pub fn derive_mutable_entity(input: TokenStream) -> TokenStream {
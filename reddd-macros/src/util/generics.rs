@@ -0,0 +1,71 @@
+use darling::ToTokens;
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Gets the parameters of `generics` that are used in `tokens`, along with
+/// the where predicates that do not mention the other ones.
+pub(crate) fn used_generics(
+    generics: &syn::Generics,
+    tokens: &TokenStream,
+) -> syn::Generics {
+    let mut names = Vec::new();
+
+    collect_names(tokens.clone(), &mut names);
+
+    let name = |param: &syn::GenericParam| match param {
+        | syn::GenericParam::Type(t) => t.ident.to_string(),
+        | syn::GenericParam::Lifetime(l) => format!("'{}", l.lifetime.ident),
+        | syn::GenericParam::Const(c) => c.ident.to_string(),
+    };
+    let (used, unused): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .cloned()
+        .partition(|param| names.contains(&name(param)));
+    let unused: Vec<_> = unused.iter().map(name).collect();
+
+    let where_clause = generics.where_clause.as_ref().map(|clause| {
+        let mut clause = clause.clone();
+
+        clause.predicates = clause
+            .predicates
+            .into_iter()
+            .filter(|predicate| {
+                let mut names = Vec::new();
+
+                collect_names(predicate.to_token_stream(), &mut names);
+
+                !names.iter().any(|n| unused.contains(n))
+            })
+            .collect();
+
+        clause
+    });
+
+    syn::Generics {
+        params: used.into_iter().collect(),
+        where_clause,
+        ..generics.clone()
+    }
+}
+
+/// Collects the identifiers and lifetimes in `tokens` into `names`.
+fn collect_names(tokens: TokenStream, names: &mut Vec<String>) {
+    let mut lifetime = false;
+
+    for token in tokens {
+        match token {
+            | TokenTree::Group(group) => collect_names(group.stream(), names),
+            | TokenTree::Ident(ident) if lifetime => {
+                names.push(format!("'{ident}"))
+            }
+            | TokenTree::Ident(ident) => names.push(ident.to_string()),
+            | TokenTree::Punct(ref punct) if punct.as_char() == '\'' => {
+                lifetime = true;
+                continue;
+            }
+            | _ => {}
+        }
+
+        lifetime = false;
+    }
+}
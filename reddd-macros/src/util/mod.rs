@@ -1,5 +1,7 @@
 mod fields;
+mod generics;
 mod structs;
 
 pub(super) use fields::*;
+pub(super) use generics::*;
 pub(super) use structs::*;
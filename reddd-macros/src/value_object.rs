@@ -0,0 +1,159 @@
+use darling::{FromDeriveInput, ToTokens};
+use quote::{format_ident, quote};
+
+use crate::util::{used_generics, StructExt};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(forward_attrs(allow, doc, cfg))]
+#[darling(attributes(value_object), supports(struct_named))]
+pub(super) struct ValueObject {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    generics: syn::Generics,
+    data: darling::ast::Data<(), syn::Field>,
+    #[darling(default, multiple, rename = "validate")]
+    validators: Vec<syn::Path>,
+    #[darling(default)]
+    unordered: bool,
+}
+
+impl ToTokens for ValueObject {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let ValueObject {
+            ref ident,
+            ref vis,
+            ref generics,
+            ref data,
+            ref validators,
+            unordered,
+        } = *self;
+
+        let name = ident.to_string();
+
+        let fields: Vec<_> = data
+            .get_fields()
+            .into_iter()
+            .map(|f| (f.ident.as_ref().unwrap(), &f.ty))
+            .collect();
+
+        // bounds the types of the fields that use generic parameters by
+        // `bound`, as the ones of derives do
+        let bounded = |bound: proc_macro2::TokenStream| {
+            let predicates = fields
+                .iter()
+                .filter(|(_, ty)| {
+                    !used_generics(generics, &ty.to_token_stream())
+                        .params
+                        .is_empty()
+                })
+                .map(|(_, ty)| -> syn::WherePredicate {
+                    syn::parse_quote!(#ty: #bound)
+                });
+            let mut generics = generics.clone();
+
+            generics.make_where_clause().predicates.extend(predicates);
+            generics
+        };
+
+        let eq_generics = bounded(quote!(PartialEq));
+        let (imp, ty, wher) = eq_generics.split_for_impl();
+        let mut vo_generics = eq_generics.clone();
+
+        vo_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(Self: Clone));
+
+        let (vo_imp, _, vo_wher) = vo_generics.split_for_impl();
+
+        let validate = (!validators.is_empty()).then(|| {
+            quote! {
+                fn validate(&self) -> Result<(), ValidationError> {
+                    #(
+                        #validators(self).map_err(|reason| {
+                            ValidationError::new(#name, reason)
+                        })?;
+                    )*
+
+                    Ok(())
+                }
+            }
+        });
+
+        let idents = fields.iter().map(|(id, _)| id);
+        let eq = match fields.is_empty() {
+            | true => quote!(true),
+            | false => quote!(#(self.#idents == other.#idents)&&*),
+        };
+
+        let partial_ord = (!unordered).then(|| {
+            let ord_generics = bounded(quote!(PartialOrd));
+            let (imp, ty, wher) = ord_generics.split_for_impl();
+            let idents = fields.iter().map(|(id, _)| id);
+
+            quote! {
+                #[automatically_derived]
+                impl #imp PartialOrd for #ident #ty #wher {
+                    fn partial_cmp(
+                        &self,
+                        other: &Self,
+                    ) -> Option<std::cmp::Ordering> {
+                        #(
+                            match PartialOrd::partial_cmp(
+                                &self.#idents,
+                                &other.#idents,
+                            ) {
+                                | Some(std::cmp::Ordering::Equal) => {}
+                                | ordering => return ordering,
+                            }
+                        )*
+
+                        Some(std::cmp::Ordering::Equal)
+                    }
+                }
+            }
+        });
+
+        let rest = (fields.len() > 1).then(|| quote!(..self.clone()));
+        let withs = fields.iter().map(|(id, ty)| {
+            let method = format_ident!("with_{}", id);
+            let doc = format!(
+                "Returns a validated copy of `self` with the `{id}` field set \
+                 to `{id}`."
+            );
+
+            quote! {
+                #[doc = #doc]
+                #vis fn #method(
+                    &self,
+                    #id: #ty,
+                ) -> Result<Self, ValidationError> {
+                    Self {
+                        #id,
+                        #rest
+                    }
+                    .validated()
+                }
+            }
+        });
+
+        tokens.extend(quote! {
+            impl #vo_imp ValueObject for #ident #ty #vo_wher {
+                #validate
+            }
+
+            #[automatically_derived]
+            impl #imp PartialEq for #ident #ty #wher {
+                fn eq(&self, other: &Self) -> bool {
+                    #eq
+                }
+            }
+
+            #partial_ord
+
+            impl #vo_imp #ident #ty #vo_wher {
+                #(#withs)*
+            }
+        });
+    }
+}
@@ -1,4 +1,11 @@
-use darling::{FromDeriveInput, FromField, ToTokens};
+use darling::{
+    util::Override,
+    FromDeriveInput,
+    FromField,
+    FromMeta,
+    FromVariant,
+    ToTokens,
+};
 use quote::quote;
 
 #[derive(Clone, Debug, FromField)]
@@ -9,16 +16,114 @@ struct ValueTypeField {
     attrs: Vec<syn::Attribute>,
 }
 
+#[derive(Clone, Debug, FromVariant)]
+#[darling(attributes(value_type))]
+struct ValueTypeVariant {
+    ident: syn::Ident,
+    #[darling(default)]
+    name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromMeta)]
+enum Comparison {
+    #[darling(rename = "main_field")]
+    MainField,
+    #[darling(rename = "all_fields")]
+    AllFields,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, FromMeta)]
+enum SensitiveSerialize {
+    /// Serializing fails with an error.
+    #[default]
+    #[darling(rename = "refuse")]
+    Refuse,
+    /// `Serialize` is not implemented.
+    #[darling(rename = "skip")]
+    Skip,
+    /// The main field is serialized in the clear.
+    #[darling(rename = "exposed")]
+    Exposed,
+}
+
+#[derive(Clone, Debug, Default, FromMeta)]
+struct ValueTypeSensitive {
+    #[darling(default)]
+    serialize: SensitiveSerialize,
+}
+
+#[derive(Clone, Debug, Default, FromMeta)]
+struct ValueTypeOps {
+    #[darling(default)]
+    add: bool,
+    #[darling(default)]
+    sub: bool,
+    #[darling(default)]
+    mul_scalar: bool,
+    #[darling(default)]
+    checked: bool,
+}
+
+#[derive(Clone, Debug, Default, FromMeta)]
+struct ValueTypeSchema {
+    #[darling(default)]
+    min: Option<syn::Lit>,
+    #[darling(default)]
+    max: Option<syn::Lit>,
+    #[darling(default)]
+    min_length: Option<u64>,
+    #[darling(default)]
+    max_length: Option<u64>,
+    #[darling(default)]
+    pattern: Option<String>,
+}
+
 #[derive(Debug, FromDeriveInput)]
 #[darling(forward_attrs(allow, doc, cfg))]
-#[darling(attributes(main_field), supports(struct_any))]
+#[darling(attributes(value_type), supports(struct_any, enum_unit))]
 pub(super) struct ValueType {
     ident: syn::Ident,
+    vis: syn::Visibility,
     generics: syn::Generics,
-    data: darling::ast::Data<(), ValueTypeField>,
+    data: darling::ast::Data<ValueTypeVariant, ValueTypeField>,
+    #[darling(default)]
+    inner: Option<syn::Type>,
+    #[darling(default)]
+    ops: Option<ValueTypeOps>,
+    #[darling(default)]
+    validate: Option<syn::Path>,
+    #[darling(default)]
+    compare: Option<Comparison>,
+    #[darling(default)]
+    sensitive: Option<Override<ValueTypeSensitive>>,
+    #[darling(default)]
+    schema: Option<Override<ValueTypeSchema>>,
+    #[darling(default)]
+    openapi: Option<Override<ValueTypeSchema>>,
+    #[darling(default)]
+    proptest: Option<Override<ValueTypeSchema>>,
+    #[darling(default)]
+    arbitrary: Option<Override<ValueTypeSchema>>,
+}
+
+impl ValueTypeVariant {
+    fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.ident.to_string())
+    }
 }
 
 impl ValueTypeField {
+    fn is_phantom(&self) -> bool {
+        match self.ty {
+            | syn::Type::Path(ref path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "PhantomData"),
+            | _ => false,
+        }
+    }
+
     fn is_main_field(&self) -> bool {
         self.attrs.iter().any(|a| {
             a.path()
@@ -33,11 +138,82 @@ impl ToTokens for ValueType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let ValueType {
             ref ident,
+            ref vis,
             ref generics,
             ref data,
+            ref inner,
+            ref ops,
+            ref validate,
+            compare,
+            ref sensitive,
+            ref schema,
+            ref openapi,
+            ref proptest,
+            ref arbitrary,
         } = *self;
 
         let (imp, ty, wher) = generics.split_for_impl();
+        let serialize = sensitive
+            .as_ref()
+            .map(|sensitive| sensitive.clone().unwrap_or_default().serialize);
+        let sensitive = serialize.is_some();
+
+        if let darling::ast::Data::Enum(variants) = data {
+            if ops.is_some() {
+                panic!("`ops` is not supported for enums");
+            }
+
+            if validate.is_some() {
+                panic!("`validate` is not supported for enums");
+            }
+
+            if compare.is_some() {
+                panic!("`compare` is not supported for enums");
+            }
+
+            if sensitive {
+                panic!("sensitive enums are not supported");
+            }
+
+            if let Some(schema) = schema {
+                if schema.is_explicit() {
+                    panic!("schema constraints are not supported for enums");
+                }
+
+                tokens.extend(enum_schema(ident, variants, inner.as_ref()));
+            }
+
+            if let Some(openapi) = openapi {
+                if openapi.is_explicit() {
+                    panic!("schema constraints are not supported for enums");
+                }
+
+                tokens.extend(enum_openapi(ident, variants, inner.as_ref()));
+            }
+
+            if let Some(proptest) = proptest {
+                if proptest.is_explicit() {
+                    panic!("generator constraints are not supported for enums");
+                }
+
+                tokens.extend(enum_proptest(ident, variants));
+            }
+
+            if let Some(arbitrary) = arbitrary {
+                if arbitrary.is_explicit() {
+                    panic!("generator constraints are not supported for enums");
+                }
+
+                tokens.extend(enum_arbitrary(ident, variants));
+            }
+
+            return tokens.extend(enum_value_type(
+                ident,
+                generics,
+                variants,
+                inner.as_ref(),
+            ));
+        }
 
         let fields: Vec<_> = data
             .as_ref()
@@ -74,12 +250,152 @@ impl ToTokens for ValueType {
             (field_id, field_ty)
         };
 
+        let other_fields: Vec<_> = fields
+            .iter()
+            .filter(|(_, id, _)| id.to_string() != field_id.to_string())
+            .map(|(_, id, ty)| (id, *ty))
+            .collect();
+        let others: Vec<_> = other_fields.iter().map(|(id, _)| *id).collect();
+
+        let compared_fields = data
+            .as_ref()
+            .take_struct()
+            .unwrap()
+            .fields
+            .into_iter()
+            .filter(|f| !f.is_phantom())
+            .count();
+
+        let compare = match compare {
+            | Some(compare) => compare,
+            | None if compared_fields <= 1 => Comparison::MainField,
+            | None => panic!(
+                "value types with multiple fields must specify how they are \
+                 compared, with either `#[value_type(compare = \
+                 \"main_field\")]` or `#[value_type(compare = \
+                 \"all_fields\")]`"
+            ),
+        };
+
+        let partial_cmp = match compare {
+            | Comparison::MainField => quote! {
+                PartialOrd::partial_cmp(&self.#field_id, &other.#field_id)
+            },
+            | Comparison::AllFields => {
+                let compared = std::iter::once(field_id).chain(others.clone());
+
+                quote! {
+                    #(
+                        match PartialOrd::partial_cmp(&self.#compared, &other.#compared) {
+                            Some(std::cmp::Ordering::Equal) => {}
+                            ordering => return ordering,
+                        }
+                    )*
+
+                    Some(std::cmp::Ordering::Equal)
+                }
+            }
+        };
+
+        if let Some(ops) = ops {
+            if sensitive {
+                panic!("operators are not supported for sensitive value types");
+            }
+
+            if compared_fields > 1 {
+                panic!(
+                    "operators are only supported for value types whose other \
+                     fields are `PhantomData` markers, as they would be lost"
+                );
+            }
+
+            let validate = validate.as_ref().unwrap_or_else(|| {
+                panic!(
+                    "`validate` is required with `ops(..)`, as the results of \
+                     operators must uphold the constraints of the value type"
+                )
+            });
+
+            tokens.extend(struct_ops(
+                ident,
+                vis,
+                generics,
+                (field_id, field_ty),
+                &others,
+                ops,
+                validate,
+            ));
+        } else if validate.is_some()
+            && proptest.is_none()
+            && arbitrary.is_none()
+        {
+            panic!(
+                "`validate` is only used by operators and generators, set \
+                 with `ops(..)`, `proptest` or `arbitrary`"
+            );
+        }
+
+        // sensitive values are zeroized on drop, so they cannot be moved out
+        let into_inner = match sensitive {
+            | true => quote!(Clone::clone(&self.#field_id)),
+            | false => quote!(self.#field_id),
+        };
+
+        if let Some(serialize) = serialize {
+            tokens.extend(sensitive_value_type(
+                ident,
+                generics,
+                (field_id, field_ty),
+                serialize,
+            ));
+        }
+
+        if let Some(schema) = schema {
+            tokens.extend(struct_schema(
+                ident,
+                generics,
+                field_ty,
+                &schema.clone().unwrap_or_default(),
+            ));
+        }
+
+        if let Some(openapi) = openapi {
+            tokens.extend(struct_openapi(
+                ident,
+                generics,
+                field_ty,
+                &openapi.clone().unwrap_or_default(),
+            ));
+        }
+
+        if let Some(proptest) = proptest {
+            tokens.extend(struct_proptest(
+                ident,
+                generics,
+                (field_id, field_ty),
+                &other_fields,
+                &proptest.clone().unwrap_or_default(),
+                validate.as_ref(),
+            ));
+        }
+
+        if let Some(arbitrary) = arbitrary {
+            tokens.extend(struct_arbitrary(
+                ident,
+                generics,
+                (field_id, field_ty),
+                &other_fields,
+                &arbitrary.clone().unwrap_or_default(),
+                validate.as_ref(),
+            ));
+        }
+
         tokens.extend(quote::quote! {
             impl #imp ValueType for #ident #ty #wher {
                 type Inner = #field_ty;
 
                 fn into_inner(self) -> Self::Inner {
-                    self.#field_id
+                    #into_inner
                 }
 
                 fn as_inner(&self) -> &Self::Inner {
@@ -95,9 +411,941 @@ impl ToTokens for ValueType {
 
             impl #imp PartialOrd for #ident #ty #wher {
                 fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                    self.#field_id.partial_cmp(&other.#field_id)
+                    #partial_cmp
+                }
+            }
+        });
+    }
+}
+
+/// Generates the arithmetic operators of a numeric value type, which operate
+/// on its main field, then check the result with `validate`. The other
+/// fields of the value type are all `PhantomData` markers.
+///
+/// Operators panic if the result is invalid, as is the case with overflows,
+/// while their checked variants return an error instead.
+fn struct_ops(
+    ident: &syn::Ident,
+    vis: &syn::Visibility,
+    generics: &syn::Generics,
+    (field_id, field_ty): (&proc_macro2::TokenStream, &syn::Type),
+    others: &[&proc_macro2::TokenStream],
+    ops: &ValueTypeOps,
+    validate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let ty_name = ident.to_string();
+    let (_, ty, _) = generics.split_for_impl();
+    let split_with = |bounds: proc_macro2::TokenStream| {
+        let mut generics = generics.clone();
+
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#field_ty: #bounds));
+
+        generics
+    };
+
+    let rest = (!others.is_empty()).then(|| quote!(..self));
+    let validated = |op: &str| {
+        quote! {
+            if let Err(reason) = #validate(&value) {
+                panic!(
+                    "invalid result of `{}` on `{}`: {}",
+                    #op,
+                    #ty_name,
+                    reason.to_string(),
+                );
+            }
+        }
+    };
+    let checked = quote! {
+        #validate(&value).map_err(|reason| {
+            ArithmeticError::Invalid(reason.to_string())
+        })?;
+    };
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    let mut checked_fns = Vec::new();
+
+    for (enabled, op, tr, method, bounds) in [
+        (
+            ops.add,
+            "add",
+            quote!(std::ops::Add),
+            quote!(add),
+            quote!(std::ops::Add<Output = #field_ty>),
+        ),
+        (
+            ops.sub,
+            "sub",
+            quote!(std::ops::Sub),
+            quote!(sub),
+            quote!(std::ops::Sub<Output = #field_ty>),
+        ),
+    ] {
+        if !enabled {
+            continue;
+        }
+
+        let generics = split_with(bounds);
+        let (imp, _, wher) = generics.split_for_impl();
+        let validated = validated(op);
+        let checked_method = quote::format_ident!("checked_{}", op);
+        let doc = format!(
+            "Computes `self {} rhs`, returning an error on overflow or if the \
+             result is invalid.",
+            if op == "add" { '+' } else { '-' }
+        );
+
+        tokens.extend(quote! {
+            impl #imp #tr for #ident #ty #wher {
+                type Output = Self;
+
+                fn #method(self, rhs: Self) -> Self::Output {
+                    let value = #tr::#method(self.#field_id, rhs.#field_id);
+
+                    #validated
+
+                    Self { #field_id: value, #rest }
+                }
+            }
+        });
+
+        checked_fns.push(quote! {
+            #[doc = #doc]
+            #vis fn #checked_method(
+                self,
+                rhs: Self,
+            ) -> Result<Self, ArithmeticError> {
+                let value = self
+                    .#field_id
+                    .#checked_method(rhs.#field_id)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                #checked
+
+                Ok(Self { #field_id: value, #rest })
+            }
+        });
+    }
+
+    if ops.add {
+        let generics =
+            split_with(quote!(std::ops::Add<Output = #field_ty> + Default));
+        let (imp, _, wher) = generics.split_for_impl();
+        let validated = validated("sum");
+
+        tokens.extend(quote! {
+            impl #imp std::iter::Sum for #ident #ty #wher {
+                fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                    let value = iter.fold(
+                        <#field_ty as Default>::default(),
+                        |acc, item| acc + item.#field_id,
+                    );
+
+                    #validated
+
+                    Self {
+                        #field_id: value,
+                        #(#others: std::marker::PhantomData,)*
+                    }
+                }
+            }
+        });
+
+        checked_fns.push(quote! {
+            /// Sums the items of `iter`, returning an error on overflow or
+            /// if the result is invalid.
+            #vis fn checked_sum<I>(iter: I) -> Result<Self, ArithmeticError>
+            where
+                I: IntoIterator<Item = Self>,
+            {
+                let value = iter.into_iter().try_fold(
+                    <#field_ty as Default>::default(),
+                    |acc, item| acc.checked_add(item.#field_id),
+                )
+                .ok_or(ArithmeticError::Overflow)?;
+
+                #checked
+
+                Ok(Self {
+                    #field_id: value,
+                    #(#others: std::marker::PhantomData,)*
+                })
+            }
+        });
+    }
+
+    if ops.mul_scalar {
+        let generics = split_with(quote!(std::ops::Mul<Output = #field_ty>));
+        let (imp, _, wher) = generics.split_for_impl();
+        let validated = validated("mul");
+
+        tokens.extend(quote! {
+            impl #imp std::ops::Mul<#field_ty> for #ident #ty #wher {
+                type Output = Self;
+
+                fn mul(self, rhs: #field_ty) -> Self::Output {
+                    let value = self.#field_id * rhs;
+
+                    #validated
+
+                    Self { #field_id: value, #rest }
+                }
+            }
+        });
+
+        checked_fns.push(quote! {
+            /// Computes `self * rhs`, returning an error on overflow or if
+            /// the result is invalid.
+            #vis fn checked_mul(
+                self,
+                rhs: #field_ty,
+            ) -> Result<Self, ArithmeticError> {
+                let value = self
+                    .#field_id
+                    .checked_mul(rhs)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                #checked
+
+                Ok(Self { #field_id: value, #rest })
+            }
+        });
+    }
+
+    if ops.checked {
+        let (imp, _, wher) = generics.split_for_impl();
+
+        tokens.extend(quote! {
+            impl #imp #ident #ty #wher {
+                #(#checked_fns)*
+            }
+        });
+    }
+
+    tokens
+}
+
+/// Generates the implementations of a sensitive value type, which redact its
+/// value when debugged or displayed, and zeroize it when dropped. Unless
+/// skipped, `Serialize` is implemented as well, which either fails or
+/// exposes the main field.
+fn sensitive_value_type(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    (field_id, field_ty): (&proc_macro2::TokenStream, &syn::Type),
+    serialize: SensitiveSerialize,
+) -> proc_macro2::TokenStream {
+    let (imp, ty, wher) = generics.split_for_impl();
+    let debug = format!("{ident}([REDACTED])");
+    let refused = format!("`{ident}` is sensitive and cannot be serialized");
+
+    let serialize = match serialize {
+        | SensitiveSerialize::Refuse => quote! {
+            impl #imp serde::Serialize for #ident #ty #wher {
+                fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    Err(<S::Error as serde::ser::Error>::custom(#refused))
+                }
+            }
+        },
+        | SensitiveSerialize::Skip => quote!(),
+        | SensitiveSerialize::Exposed => {
+            let mut generics = generics.clone();
+
+            generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote!(#field_ty: serde::Serialize));
+
+            let (imp, _, wher) = generics.split_for_impl();
+
+            quote! {
+                impl #imp serde::Serialize for #ident #ty #wher {
+                    fn serialize<S>(
+                        &self,
+                        serializer: S,
+                    ) -> Result<S::Ok, S::Error>
+                    where
+                        S: serde::Serializer,
+                    {
+                        serde::Serialize::serialize(&self.#field_id, serializer)
+                    }
                 }
             }
+        }
+    };
+
+    quote! {
+        #serialize
+
+        impl #imp std::fmt::Debug for #ident #ty #wher {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(#debug)
+            }
+        }
+
+        impl #imp std::fmt::Display for #ident #ty #wher {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("[REDACTED]")
+            }
+        }
+
+        impl #imp Drop for #ident #ty #wher {
+            fn drop(&mut self) {
+                Zeroize::zeroize(&mut self.#field_id);
+            }
+        }
+    }
+}
+
+/// Generates the JSON schema of a value type, which is the one of its main
+/// field, narrowed down by the constraints declared in
+/// `#[value_type(schema(..))]`.
+fn struct_schema(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    field_ty: &syn::Type,
+    schema: &ValueTypeSchema,
+) -> proc_macro2::TokenStream {
+    let name = ident.to_string();
+    let mut generics = generics.clone();
+
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#field_ty: JsonSchema));
+
+    let (imp, ty, wher) = generics.split_for_impl();
+
+    let mut constraints = Vec::new();
+
+    for (key, value) in [("minimum", &schema.min), ("maximum", &schema.max)] {
+        if let Some(value) = value {
+            constraints.push(quote! {
+                schema.insert(#key.to_owned(), #value.into());
+            });
+        }
+    }
+
+    // lengths apply to the items of arrays, and to the characters of strings
+    for (string_key, array_key, length) in [
+        ("minLength", "minItems", schema.min_length),
+        ("maxLength", "maxItems", schema.max_length),
+    ] {
+        if let Some(length) = length {
+            constraints.push(quote! {
+                let key = match schema.get("type").and_then(|t| t.as_str()) {
+                    | Some("array") => #array_key,
+                    | _ => #string_key,
+                };
+
+                schema.insert(key.to_owned(), #length.into());
+            });
+        }
+    }
+
+    if let Some(ref pattern) = schema.pattern {
+        constraints.push(quote! {
+            schema.insert("pattern".to_owned(), #pattern.into());
         });
     }
+
+    quote! {
+        impl #imp JsonSchema for #ident #ty #wher {
+            fn inline_schema() -> bool {
+                true
+            }
+
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+                let mut schema = <#field_ty as JsonSchema>::json_schema(generator);
+
+                #(#constraints)*
+
+                schema
+            }
+        }
+    }
+}
+
+/// Generates the JSON schema of a C-like enum value type, which enumerates
+/// the inner values of its variants.
+fn enum_schema(
+    ident: &syn::Ident,
+    variants: &[ValueTypeVariant],
+    inner: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let name = ident.to_string();
+    let (inner_ty, values) = enum_inner_values(ident, variants, inner);
+
+    quote! {
+        impl JsonSchema for #ident {
+            fn inline_schema() -> bool {
+                true
+            }
+
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+                let mut schema = <#inner_ty as JsonSchema>::json_schema(generator);
+
+                schema.insert("enum".to_owned(), vec![#(#values),*].into());
+
+                schema
+            }
+        }
+    }
+}
+
+/// Generates the OpenAPI schema of a value type, which is the one of its main
+/// field, narrowed down by the constraints declared in
+/// `#[value_type(openapi(..))]`.
+fn struct_openapi(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    field_ty: &syn::Type,
+    schema: &ValueTypeSchema,
+) -> proc_macro2::TokenStream {
+    let mut generics = generics.clone();
+
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#field_ty: ValueSchema));
+
+    let (imp, ty, wher) = generics.split_for_impl();
+
+    let ValueTypeSchema {
+        ref min,
+        ref max,
+        min_length,
+        max_length,
+        ref pattern,
+    } = *schema;
+    let mut constraints = Vec::new();
+
+    if let Some(min) = min {
+        constraints.push(quote!(minimum: Some((#min).into())));
+    }
+
+    if let Some(max) = max {
+        constraints.push(quote!(maximum: Some((#max).into())));
+    }
+
+    if let Some(min_length) = min_length {
+        let min_length = proc_macro2::Literal::u64_unsuffixed(min_length);
+
+        constraints.push(quote!(min_length: Some(#min_length)));
+    }
+
+    if let Some(max_length) = max_length {
+        let max_length = proc_macro2::Literal::u64_unsuffixed(max_length);
+
+        constraints.push(quote!(max_length: Some(#max_length)));
+    }
+
+    if let Some(pattern) = pattern {
+        constraints.push(quote!(pattern: Some(#pattern)));
+    }
+
+    let value_schema = match constraints.is_empty() {
+        | true => quote!(<#field_ty as ValueSchema>::value_schema()),
+        | false => quote! {
+            SchemaConstraints {
+                #(#constraints,)*
+                ..Default::default()
+            }
+            .apply(<#field_ty as ValueSchema>::value_schema())
+        },
+    };
+
+    quote! {
+        impl #imp ValueSchema for #ident #ty #wher {
+            fn value_schema() -> OpenApiSchema {
+                #value_schema
+            }
+        }
+
+        impl #imp PartialSchema for #ident #ty #wher {
+            fn schema() -> OpenApiSchema {
+                <Self as ValueSchema>::value_schema()
+            }
+        }
+
+        impl #imp ToSchema for #ident #ty #wher {}
+    }
+}
+
+/// Generates the OpenAPI schema of a C-like enum value type, which
+/// enumerates the inner values of its variants.
+fn enum_openapi(
+    ident: &syn::Ident,
+    variants: &[ValueTypeVariant],
+    inner: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let (inner_ty, values) = enum_inner_values(ident, variants, inner);
+
+    quote! {
+        impl ValueSchema for #ident {
+            fn value_schema() -> OpenApiSchema {
+                SchemaConstraints {
+                    enum_values: Some(vec![#((#values).into()),*]),
+                    ..Default::default()
+                }
+                .apply(<#inner_ty as ValueSchema>::value_schema())
+            }
+        }
+
+        impl PartialSchema for #ident {
+            fn schema() -> OpenApiSchema {
+                <Self as ValueSchema>::value_schema()
+            }
+        }
+
+        impl ToSchema for #ident {}
+    }
+}
+
+/// Checks whether `ty` is a string type, which lengths are counted in
+/// characters rather than bytes.
+fn is_string(ty: &syn::Type) -> bool {
+    match ty {
+        | syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "String"),
+        | syn::Type::Reference(reference) => matches!(
+            *reference.elem,
+            syn::Type::Path(ref path) if path.path.is_ident("str")
+        ),
+        | _ => false,
+    }
+}
+
+/// Gets the expression of the length of a `value` of type `ty`, in
+/// characters for strings, as in JSON schemas.
+fn length(ty: &syn::Type) -> proc_macro2::TokenStream {
+    match is_string(ty) {
+        | true => quote!(value.chars().count()),
+        | false => quote!(value.len()),
+    }
+}
+
+/// Generates the proptest strategy of a value type, which generates main
+/// fields within the constraints declared in `#[value_type(proptest(..))]`
+/// that pass `validate` if given, and arbitrary other fields.
+fn struct_proptest(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    (field_id, field_ty): (&proc_macro2::TokenStream, &syn::Type),
+    others: &[(&proc_macro2::TokenStream, &syn::Type)],
+    constraints: &ValueTypeSchema,
+    validate: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    let ValueTypeSchema {
+        ref min,
+        ref max,
+        min_length,
+        max_length,
+        ref pattern,
+    } = *constraints;
+
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+
+    for ty in std::iter::once(field_ty).chain(others.iter().map(|(_, ty)| *ty))
+    {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: proptest::arbitrary::Arbitrary));
+    }
+
+    let (imp, ty, wher) = generics.split_for_impl();
+
+    let lengths = (min_length.is_some() || max_length.is_some())
+        .then(|| (min_length.unwrap_or(0), max_length));
+    let is_string = is_string(field_ty);
+
+    // strings within lengths are generated directly rather than filtered
+    let (strategy, lengths) = match (pattern, min, max) {
+        | (Some(pattern), _, _) => (
+            quote!(proptest::string::string_regex(#pattern).expect("invalid pattern")),
+            lengths,
+        ),
+        | (None, None, None) => match lengths {
+            | Some((min_length, max_length)) if is_string => {
+                let max_length =
+                    max_length.map(|max| max.to_string()).unwrap_or_default();
+                let pattern = format!("\\PC{{{min_length},{max_length}}}");
+
+                (
+                    quote!(proptest::string::string_regex(#pattern).unwrap()),
+                    None,
+                )
+            }
+            | _ => (quote!(proptest::arbitrary::any::<#field_ty>()), lengths),
+        },
+        | (None, min, max) => {
+            let min = match min {
+                | Some(min) => quote!(#min),
+                | None => quote!(<#field_ty>::MIN),
+            };
+            let max = match max {
+                | Some(max) => quote!(#max),
+                | None => quote!(<#field_ty>::MAX),
+            };
+
+            (
+                quote! {{
+                    let range: std::ops::RangeInclusive<#field_ty> = #min..=#max;
+
+                    range
+                }},
+                lengths,
+            )
+        }
+    };
+
+    let mut filters = Vec::new();
+
+    if let Some((min_length, max_length)) = lengths {
+        let max_length = match max_length {
+            | Some(max_length) => quote!(#max_length as usize),
+            | None => quote!(usize::MAX),
+        };
+
+        let length = length(field_ty);
+
+        filters.push(quote! {
+            .prop_filter("length out of range", |value| {
+                (#min_length as usize..=#max_length).contains(&#length)
+            })
+        });
+    }
+
+    if let Some(validate) = validate {
+        filters.push(quote! {
+            .prop_filter("invalid value", |value| #validate(value).is_ok())
+        });
+    }
+
+    let other_ids: Vec<_> = others.iter().map(|(id, _)| *id).collect();
+    let other_tys = others.iter().map(|(_, ty)| *ty);
+    let other_vars: Vec<_> = (0..others.len())
+        .map(|i| quote::format_ident!("__field{}", i))
+        .collect();
+
+    quote! {
+        impl #imp proptest::arbitrary::Arbitrary for #ident #ty #wher {
+            type Parameters = ();
+            type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                use proptest::strategy::Strategy as _;
+
+                (
+                    #strategy #(#filters)*,
+                    #(proptest::arbitrary::any::<#other_tys>(),)*
+                )
+                    .prop_map(|(value, #(#other_vars,)*)| Self {
+                        #field_id: value,
+                        #(#other_ids: #other_vars,)*
+                    })
+                    .boxed()
+            }
+        }
+    }
+}
+
+/// Generates the `Arbitrary` implementation of a value type, which rejects
+/// main fields that are out of the constraints declared in
+/// `#[value_type(arbitrary(..))]` or that fail `validate` if given, except
+/// for integers, which are generated within their bounds.
+fn struct_arbitrary(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    (field_id, field_ty): (&proc_macro2::TokenStream, &syn::Type),
+    others: &[(&proc_macro2::TokenStream, &syn::Type)],
+    constraints: &ValueTypeSchema,
+    validate: Option<&syn::Path>,
+) -> proc_macro2::TokenStream {
+    let ValueTypeSchema {
+        ref min,
+        ref max,
+        min_length,
+        max_length,
+        ref pattern,
+    } = *constraints;
+
+    if pattern.is_some() {
+        panic!(
+            "patterns are not supported by `arbitrary`, check values with \
+             `validate` instead"
+        );
+    }
+
+    let (_, ty, _) = generics.split_for_impl();
+    let mut generics = generics.clone();
+
+    generics.params.insert(0, syn::parse_quote!('arbitrary));
+
+    let where_clause = generics.make_where_clause();
+
+    for ty in std::iter::once(field_ty).chain(others.iter().map(|(_, ty)| *ty))
+    {
+        where_clause.predicates.push(syn::parse_quote! {
+            #ty: arbitrary::Arbitrary<'arbitrary>
+        });
+    }
+
+    let (imp, _, wher) = generics.split_for_impl();
+
+    let is_int = |lit: &Option<syn::Lit>| {
+        lit.as_ref()
+            .is_none_or(|lit| matches!(lit, syn::Lit::Int(_)))
+    };
+    let mut checks = Vec::new();
+
+    let value = match (min, max) {
+        | (None, None) => quote!(arbitrary::Arbitrary::arbitrary(u)?),
+        | (min, max) if is_int(min) && is_int(max) => {
+            let min = match min {
+                | Some(min) => quote!(#min),
+                | None => quote!(<#field_ty>::MIN),
+            };
+            let max = match max {
+                | Some(max) => quote!(#max),
+                | None => quote!(<#field_ty>::MAX),
+            };
+
+            quote!(u.int_in_range(#min..=#max)?)
+        }
+        | (min, max) => {
+            if let Some(min) = min {
+                checks.push(quote!(value >= #min));
+            }
+
+            if let Some(max) = max {
+                checks.push(quote!(value <= #max));
+            }
+
+            quote!(arbitrary::Arbitrary::arbitrary(u)?)
+        }
+    };
+
+    let length = length(field_ty);
+
+    if let Some(min_length) = min_length {
+        checks.push(quote!(#length as u64 >= #min_length));
+    }
+
+    if let Some(max_length) = max_length {
+        checks.push(quote!(#length as u64 <= #max_length));
+    }
+
+    if let Some(validate) = validate {
+        checks.push(quote!(#validate(&value).is_ok()));
+    }
+
+    let other_ids = others.iter().map(|(id, _)| *id);
+
+    quote! {
+        impl #imp arbitrary::Arbitrary<'arbitrary> for #ident #ty #wher {
+            fn arbitrary(
+                u: &mut arbitrary::Unstructured<'arbitrary>,
+            ) -> arbitrary::Result<Self> {
+                let value: #field_ty = #value;
+
+                #(
+                    if !(#checks) {
+                        return Err(arbitrary::Error::IncorrectFormat);
+                    }
+                )*
+
+                Ok(Self {
+                    #field_id: value,
+                    #(#other_ids: arbitrary::Arbitrary::arbitrary(u)?,)*
+                })
+            }
+        }
+    }
+}
+
+/// Generates the proptest strategy of a C-like enum value type, which
+/// selects one of its variants.
+fn enum_proptest(
+    ident: &syn::Ident,
+    variants: &[ValueTypeVariant],
+) -> proc_macro2::TokenStream {
+    let idents = variants.iter().map(|v| &v.ident);
+
+    quote! {
+        impl proptest::arbitrary::Arbitrary for #ident {
+            type Parameters = ();
+            type Strategy = proptest::sample::Select<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                proptest::sample::select(vec![#(Self::#idents),*])
+            }
+        }
+    }
+}
+
+/// Generates the `Arbitrary` implementation of a C-like enum value type,
+/// which chooses one of its variants.
+fn enum_arbitrary(
+    ident: &syn::Ident,
+    variants: &[ValueTypeVariant],
+) -> proc_macro2::TokenStream {
+    let count = variants.len();
+    let indices = 0..count;
+    let idents = variants.iter().map(|v| &v.ident);
+
+    quote! {
+        impl<'arbitrary> arbitrary::Arbitrary<'arbitrary> for #ident {
+            fn arbitrary(
+                u: &mut arbitrary::Unstructured<'arbitrary>,
+            ) -> arbitrary::Result<Self> {
+                match u.choose_index(#count)? {
+                    #(#indices => Ok(Self::#idents),)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+/// Gets the type of the inner values of a C-like enum value type, and the
+/// inner value of each of its variants.
+fn enum_inner_values(
+    ident: &syn::Ident,
+    variants: &[ValueTypeVariant],
+    inner: Option<&syn::Type>,
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    match inner {
+        | Some(inner) => (
+            quote!(#inner),
+            variants
+                .iter()
+                .map(|v| {
+                    let v = &v.ident;
+
+                    quote!(#ident::#v as #inner)
+                })
+                .collect(),
+        ),
+        | None => (
+            quote!(&'static str),
+            variants
+                .iter()
+                .map(|v| {
+                    let name = v.name();
+
+                    quote!(#name)
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Generates the implementation of a C-like enum value type, which inner
+/// value is the name of the variant (or the one set with
+/// `#[value_type(name = "...")]`), or its discriminant cast to `inner` if
+/// given.
+fn enum_value_type(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[ValueTypeVariant],
+    inner: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    if !generics.params.is_empty() {
+        panic!("generic enums are not supported");
+    }
+
+    if variants.is_empty() {
+        panic!("enums without variants are not supported");
+    }
+
+    let idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let (inner_ty, values, try_from) = match inner {
+        | Some(inner) => (
+            quote!(#inner),
+            idents.iter().map(|v| quote!(Self::#v as #inner)).collect(),
+            quote!(impl TryFrom<#inner> for #ident {
+                type Error = #inner;
+
+                fn try_from(value: #inner) -> Result<Self, Self::Error> {
+                    #(
+                        if value == Self::#idents as #inner {
+                            return Ok(Self::#idents);
+                        }
+                    )*
+
+                    Err(value)
+                }
+            }),
+        ),
+        | None => {
+            let names: Vec<_> =
+                variants.iter().map(ValueTypeVariant::name).collect();
+
+            (
+                quote!(&'static str),
+                names.iter().map(|name| quote!(#name)).collect::<Vec<_>>(),
+                quote!(impl<'a> TryFrom<&'a str> for #ident {
+                    type Error = &'a str;
+
+                    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+                        match value {
+                            #(#names => Ok(Self::#idents),)*
+                            | _ => Err(value),
+                        }
+                    }
+                }),
+            )
+        }
+    };
+
+    quote! {
+        impl ValueType for #ident {
+            type Inner = #inner_ty;
+
+            fn into_inner(self) -> Self::Inner {
+                match self {
+                    #(Self::#idents => #values,)*
+                }
+            }
+
+            fn as_inner(&self) -> &Self::Inner {
+                match self {
+                    #(Self::#idents => &(#values),)*
+                }
+            }
+        }
+
+        #try_from
+
+        impl PartialEq for #ident {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_inner() == other.as_inner()
+            }
+        }
+
+        impl PartialOrd for #ident {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.as_inner().partial_cmp(other.as_inner())
+            }
+        }
+    }
 }
@@ -0,0 +1,211 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+
+use super::InvariantViolation;
+
+/// A trait to be implemented by generators of entity keys.
+///
+/// This trait is implemented for all `Fn() -> K` closures and functions.
+///
+/// # Example
+///
+/// ```ignore
+/// let user = User::builder()
+///     .name("John")
+///     .build_with(&|| Key::new(Uuid::new_v4()), &SystemClock)?;
+/// ```
+pub trait KeyGenerator<K> {
+    /// Generates a new key.
+    fn generate(&self) -> K;
+}
+
+impl<K, F> KeyGenerator<K> for F
+where
+    F: Fn() -> K,
+{
+    fn generate(&self) -> K {
+        self()
+    }
+}
+
+/// A trait to be implemented by clocks, which are used to timestamp entities.
+///
+/// This trait is implemented for all `Fn() -> DateTime<Utc>` closures and
+/// functions.
+pub trait Clock {
+    /// Gets the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> DateTime<Utc>,
+{
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+/// A [`Clock`] that reads the system time through [`Utc::now()`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// An enumeration of possible errors that can occur when building an
+/// entity.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum BuildError {
+    /// A value that could not be converted to the type of a field was
+    /// provided.
+    #[error("invalid value of field `{field}`: {reason}")]
+    InvalidField {
+        /// The name of the field.
+        field: &'static str,
+
+        /// The reason the conversion failed for.
+        reason: String,
+    },
+
+    /// The built entity violates one of its invariants.
+    #[error(transparent)]
+    InvariantViolated(#[from] InvariantViolation),
+}
+
+/// A type-state marker of a builder field that was not set yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unset;
+
+/// A type-state marker of a builder field that was set, which holds the
+/// result of converting the provided value to the type of the field.
+#[derive(Clone, Debug)]
+pub struct Set<T>(Result<T, BuildError>);
+
+impl<T> Set<T> {
+    /// Converts `value` to the type of a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the field.
+    /// * `value` - The value to convert.
+    pub fn convert<V>(field: &'static str, value: V) -> Self
+    where
+        V: TryInto<T>,
+        V::Error: Display,
+    {
+        Self(value.try_into().map_err(|err| BuildError::InvalidField {
+            field,
+            reason: err.to_string(),
+        }))
+    }
+
+    /// Moves the converted value out of `self`.
+    pub fn into_inner(self) -> Result<T, BuildError> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use reddd_macros::{EntityBuilder, MutableEntity, ValueType};
+
+    use super::*;
+    use crate::domain::{Entity, Key, MutableEntity, ValueType};
+
+    #[derive(Clone, Debug, ValueType)]
+    struct Email(String);
+
+    impl TryFrom<&str> for Email {
+        type Error = &'static str;
+
+        fn try_from(value: &str) -> Result<Self, Self::Error> {
+            match value.contains('@') {
+                | true => Ok(Self(value.into())),
+                | false => Err("missing `@`"),
+            }
+        }
+    }
+
+    #[derive(Debug, MutableEntity, EntityBuilder)]
+    #[builder(key_generator = next_key)]
+    #[entity(invariant = adult)]
+    struct User {
+        id: Key<User, u32>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        name: String,
+        email: Email,
+        age: u8,
+    }
+
+    fn next_key() -> Key<User, u32> {
+        Key::new(7)
+    }
+
+    fn adult(user: &User) -> Result<(), &'static str> {
+        match user.age >= 18 {
+            | true => Ok(()),
+            | false => Err("users must be adults"),
+        }
+    }
+
+    #[test]
+    fn build_test() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let user = User::builder()
+            .age(30)
+            .name("John")
+            .email("john@example.com")
+            .build_with(&|| Key::new(1), &|| at)
+            .unwrap();
+
+        assert_eq!(&Key::new(1), user.id());
+        assert_eq!(&at, user.created_at());
+        assert_eq!(&at, user.updated_at());
+        assert_eq!("John", user.name);
+        assert_eq!("john@example.com", user.email.as_inner());
+
+        let user = User::builder()
+            .name("Jane")
+            .email("jane@example.com")
+            .age(20)
+            .build()
+            .unwrap();
+
+        assert_eq!(&Key::new(7), user.id());
+        assert_eq!(user.created_at(), user.updated_at());
+    }
+
+    #[test]
+    fn build_error_test() {
+        let err = User::builder()
+            .name("John")
+            .email("john")
+            .age(30)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            BuildError::InvalidField {
+                field: "email",
+                reason: "missing `@`".into(),
+            },
+            err
+        );
+
+        let err = User::builder()
+            .name("John")
+            .email("john@example.com")
+            .age(3)
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(err, BuildError::InvariantViolated(_)));
+    }
+}
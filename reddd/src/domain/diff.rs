@@ -0,0 +1,177 @@
+/// A struct that holds the old and new values of a changed field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldChange<T> {
+    /// The value of the field before the change.
+    pub old: T,
+
+    /// The value of the field after the change.
+    pub new: T,
+}
+
+/// A trait to be implemented by change-sets, which hold the changed fields
+/// between two instances of a type.
+pub trait ChangeSet {
+    /// Gets the names of the changed fields, in declaration order.
+    fn changed_fields(&self) -> Vec<&'static str>;
+
+    /// Checks whether no fields were changed.
+    fn is_empty(&self) -> bool {
+        self.changed_fields().is_empty()
+    }
+}
+
+/// A trait to be implemented by types which instances can be compared field
+/// by field, to track changes between versions of the same entity (e.g. to
+/// persist dirty fields only, or to emit audit entries).
+///
+/// This trait is usually derived, which generates a `<Type>Changes`
+/// change-set struct, with an optional [`FieldChange`] for every field.
+/// Fields can be excluded with `#[diff(skip)]`, the change-set struct can be
+/// renamed with `#[diff(name = MyChanges)]`, and made serializable with
+/// `#[diff(serde)]`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Clone, MutableEntity, Diff)]
+/// #[diff(serde)]
+/// struct User {
+///     id: Key<User, Uuid>,
+///     created_at: DateTime<Utc>,
+///     #[diff(skip)]
+///     updated_at: DateTime<Utc>,
+///     name: String,
+///     email: String,
+/// }
+///
+/// let stored = users_repo.get(user.id()).await?;
+/// let changes = stored.diff(&user);
+///
+/// if !changes.is_empty() {
+///     audit_log.record(serde_json::to_value(&changes)?);
+/// }
+/// ```
+pub trait Diff {
+    /// The type of the change-sets between instances.
+    type Changes: ChangeSet;
+
+    /// Computes the change-set from `self` to `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The newer instance to compare `self` to.
+    fn diff(&self, other: &Self) -> Self::Changes;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use reddd_macros::{Diff, MutableEntity};
+
+    use super::*;
+    use crate::domain::{Entity, Key, MutableEntity};
+
+    #[derive(Clone, Debug, MutableEntity, Diff)]
+    #[cfg_attr(feature = "serde", diff(serde))]
+    struct User {
+        id: Key<User, u32>,
+        #[diff(skip)]
+        created_at: DateTime<Utc>,
+        #[diff(skip)]
+        updated_at: DateTime<Utc>,
+        name: String,
+        age: u8,
+    }
+
+    #[derive(Clone, Diff)]
+    #[diff(name = Renamed)]
+    struct Generic<T: Clone + PartialEq> {
+        value: T,
+    }
+
+    #[derive(Clone, Diff)]
+    struct Tagged<'a, T: Clone + PartialEq, M>
+    where
+        M: Clone,
+    {
+        value: T,
+        #[diff(skip)]
+        meta: &'a M,
+    }
+
+    fn user() -> User {
+        let now = Utc::now();
+
+        User {
+            id: Key::new(1),
+            created_at: now,
+            updated_at: now,
+            name: "John".into(),
+            age: 30,
+        }
+    }
+
+    #[test]
+    fn diff_test() {
+        let old = user();
+        let mut new = old.clone();
+
+        assert!(old.diff(&new).is_empty());
+
+        new.name = "Jane".into();
+        new.touch();
+
+        let changes = old.diff(&new);
+
+        assert_eq!(vec!["name"], changes.changed_fields());
+        assert_eq!(
+            Some(FieldChange {
+                old: "John".to_string(),
+                new: "Jane".to_string(),
+            }),
+            changes.name
+        );
+        assert_eq!(None, changes.age);
+        assert_eq!(None, changes.id);
+
+        let changes: Renamed<u8> =
+            Generic { value: 1 }.diff(&Generic { value: 2 });
+
+        assert_eq!(vec!["value"], changes.changed_fields());
+
+        let old = Tagged {
+            value: 1,
+            meta: &"old",
+        };
+        let new = Tagged {
+            value: 1,
+            meta: &"new",
+        };
+        let changes: TaggedChanges<u8> = old.diff(&new);
+
+        assert_ne!(old.meta, new.meta);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let old = user();
+        let mut new = old.clone();
+
+        new.age = 31;
+
+        let changes = old.diff(&new);
+        let serialized = serde_json::to_value(&changes).unwrap();
+
+        assert_eq!(
+            serde_json::json!({ "age": { "old": 30, "new": 31 } }),
+            serialized
+        );
+        assert_eq!(
+            changes,
+            serde_json::from_value::<UserChanges>(serialized).unwrap()
+        );
+    }
+}
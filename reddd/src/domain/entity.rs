@@ -16,6 +16,43 @@ pub type Key<E, V> = TypedValue<V, E>;
 ///
 /// This trait is used to identify domain entities, and expose common fields
 /// through getters.
+///
+/// When derived, the key and timestamps are read from the fields named
+/// `id` and `created_at`, or the ones annotated with `#[id_field]` and
+/// `#[created_at_field]`, which also works for tuple structs. Fields of
+/// nested structs can be referred to by path instead, in which case the type
+/// of the key must be given. Composite keys are kept in a single tuple or
+/// struct field, since keys are borrowed from entities. Enums can be entities
+/// as well, as long as all of their variants carry these fields.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(MutableEntity)]
+/// #[entity(
+///     id = "meta.id",
+///     key_type = "Key<User, Uuid>",
+///     created_at = "meta.created_at",
+///     updated_at = "meta.updated_at"
+/// )]
+/// struct User {
+///     meta: Metadata,
+///     name: String,
+/// }
+///
+/// #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// struct MembershipKey {
+///     tenant: Key<Tenant, Uuid>,
+///     user: Key<User, Uuid>,
+/// }
+///
+/// #[derive(Entity)]
+/// #[entity(id = "key")]
+/// struct Membership {
+///     key: MembershipKey,
+///     created_at: DateTime<Utc>,
+/// }
+/// ```
 pub trait Entity {
     /// The type of the key that is used to identify entities.
     type Key;
@@ -25,6 +62,79 @@ pub trait Entity {
 
     /// Gets a reference to the timestamp at which the entity was created.
     fn created_at(&self) -> &DateTime<Utc>;
+
+    /// Checks whether the entity satisfies all of its invariants.
+    ///
+    /// Entities have no invariants by default. When derived, this checks
+    /// the invariants passed with `#[entity(invariant = path::to::fn)]`, in
+    /// order, where each is a function with the signature
+    /// `fn(&Self) -> Result<(), impl ToString>`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[derive(Entity)]
+    /// #[entity(invariant = ends_after_start)]
+    /// struct Booking {
+    ///     // --snip--
+    ///     start_date: NaiveDate,
+    ///     end_date: NaiveDate,
+    /// }
+    ///
+    /// fn ends_after_start(booking: &Booking) -> Result<(), &'static str> {
+    ///     match booking.end_date > booking.start_date {
+    ///         | true => Ok(()),
+    ///         | false => Err("end date must be after start date"),
+    ///     }
+    /// }
+    /// ```
+    fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        Ok(())
+    }
+
+    /// Checks the invariants of the entity, and returns it only if they are
+    /// all satisfied, which is useful when constructing entities.
+    fn validated(self) -> Result<Self, InvariantViolation>
+    where
+        Self: Sized,
+    {
+        self.check_invariants().map(|_| self)
+    }
+}
+
+/// A struct that holds info about an entity invariant that was violated.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("invariant `{invariant}` of `{entity}` violated: {reason}")]
+pub struct InvariantViolation {
+    /// The name of the entity type.
+    pub entity: &'static str,
+
+    /// The name of the violated invariant.
+    pub invariant: &'static str,
+
+    /// The reason the invariant was violated for.
+    pub reason: String,
+}
+
+impl InvariantViolation {
+    /// Creates a new [`InvariantViolation`].
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The name of the entity type.
+    /// * `invariant` - The name of the violated invariant.
+    /// * `reason` - The reason the invariant was violated for.
+    pub fn new(
+        entity: &'static str,
+        invariant: &'static str,
+        reason: impl ToString,
+    ) -> Self {
+        Self {
+            entity,
+            invariant,
+            reason: reason.to_string(),
+        }
+    }
 }
 
 /// A trait that provides mutability support on top of [`Entity`] trait
@@ -37,6 +147,83 @@ pub trait MutableEntity: Entity {
 
     /// Updates the modification timestamp to [`chrono::Utc::now()`]
     fn touch(&mut self) -> &DateTime<Utc>;
+
+    /// Checks the invariants of the entity, then updates the modification
+    /// timestamp only if they are all satisfied.
+    fn try_touch(&mut self) -> Result<&DateTime<Utc>, InvariantViolation> {
+        self.check_invariants()?;
+
+        Ok(self.touch())
+    }
+}
+
+/// A trait that provides soft deletion support on top of [`MutableEntity`]
+/// trait.
+///
+/// Soft-deleted entities are kept in data repositories as tombstones, marked
+/// with the timestamp at which they were deleted, which makes it possible to
+/// restore them later.
+///
+/// This trait is implemented along with [`MutableEntity`] when derived, for
+/// entities that have an `Option<DateTime<Utc>>` field annotated with
+/// `#[deleted_at_field]`.
+pub trait SoftDeletableEntity: MutableEntity {
+    /// Gets the timestamp at which the entity was soft-deleted, if it was.
+    fn deleted_at(&self) -> Option<&DateTime<Utc>>;
+
+    /// Marks the entity as deleted at [`chrono::Utc::now()`], and updates
+    /// the modification timestamp accordingly.
+    fn mark_deleted(&mut self) -> &DateTime<Utc>;
+
+    /// Clears the deletion mark of the entity, and updates the modification
+    /// timestamp to [`chrono::Utc::now()`].
+    fn mark_restored(&mut self);
+
+    /// Checks whether the entity is soft-deleted.
+    fn is_deleted(&self) -> bool {
+        self.deleted_at().is_some()
+    }
+}
+
+/// A trait that provides audit trail support on top of [`MutableEntity`]
+/// trait.
+///
+/// Audited entities expose the actors who created and last updated them, in
+/// addition to the timestamps exposed by [`MutableEntity`].
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(MutableEntity)]
+/// struct Document {
+///     id: Key<Document, Uuid>,
+///     created_at: DateTime<Utc>,
+///     updated_at: DateTime<Utc>,
+///     #[created_by_field]
+///     author: Key<User, Uuid>,
+///     #[updated_by_field]
+///     last_editor: Key<User, Uuid>,
+/// }
+///
+/// document.touch_by(editor.id().clone());
+/// ```
+pub trait AuditedEntity: MutableEntity {
+    /// The type of the key that is used to identify actors.
+    type Actor;
+
+    /// Gets a reference to the key of the actor who created the entity.
+    fn created_by(&self) -> &Self::Actor;
+
+    /// Gets a reference to the key of the actor who last updated the entity.
+    fn updated_by(&self) -> &Self::Actor;
+
+    /// Updates the modification timestamp to [`chrono::Utc::now()`], and the
+    /// last updating actor to `actor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor` - The key of the actor updating the entity.
+    fn touch_by(&mut self, actor: Self::Actor) -> &DateTime<Utc>;
 }
 
 #[cfg(test)]
@@ -143,4 +330,239 @@ mod tests {
         assert_ne!(&old_timestamp, user.updated_at());
         assert_eq!(&new_timestamp, user.updated_at());
     }
+
+    #[test]
+    fn soft_deletable_entity_test() {
+        #[derive(Debug, MutableEntity, Dummy)]
+        struct User {
+            id: Key<User, Uuid>,
+            created_at: DateTime<Utc>,
+            updated_at: DateTime<Utc>,
+
+            #[deleted_at_field]
+            removed_at: Option<DateTime<Utc>>,
+        }
+
+        let mut user: User = Faker.fake();
+        user.removed_at = None;
+
+        assert!(!user.is_deleted());
+        assert_eq!(None, user.deleted_at());
+
+        let old_timestamp = *user.updated_at();
+        let deleted_at = *user.mark_deleted();
+
+        assert!(user.is_deleted());
+        assert_eq!(Some(&deleted_at), user.deleted_at());
+        assert_eq!(user.removed_at, Some(deleted_at));
+        assert_eq!(&deleted_at, user.updated_at());
+        assert_ne!(&old_timestamp, user.updated_at());
+
+        user.mark_restored();
+
+        assert!(!user.is_deleted());
+        assert_eq!(None, user.deleted_at());
+        assert_ne!(&deleted_at, user.updated_at());
+    }
+
+    #[test]
+    fn unannotated_deleted_at_test() {
+        #[derive(Debug, MutableEntity, Dummy)]
+        struct Account {
+            id: Key<Account, Uuid>,
+            created_at: DateTime<Utc>,
+            updated_at: DateTime<Utc>,
+            deleted_at: String,
+        }
+
+        let mut account: Account = Faker.fake();
+        let deleted_at = account.deleted_at.clone();
+
+        account.touch();
+
+        assert_eq!(deleted_at, account.deleted_at);
+    }
+
+    #[test]
+    fn audited_entity_test() {
+        #[derive(Debug, MutableEntity, Dummy)]
+        struct Document {
+            id: Key<Document, Uuid>,
+            created_at: DateTime<Utc>,
+            updated_at: DateTime<Utc>,
+
+            #[created_by_field]
+            author: Key<User, Uuid>,
+
+            #[updated_by_field]
+            last_editor: Key<User, Uuid>,
+        }
+
+        #[derive(Debug, Entity)]
+        struct User {
+            id: Key<User, Uuid>,
+            created_at: DateTime<Utc>,
+        }
+
+        let mut doc: Document = Faker.fake();
+
+        assert_eq!(&doc.author, doc.created_by());
+        assert_eq!(&doc.last_editor, doc.updated_by());
+
+        let author = doc.author.clone();
+        let editor: Key<User, Uuid> = Faker.fake();
+        let old_timestamp = *doc.updated_at();
+        let touched_at = *doc.touch_by(editor.clone());
+
+        assert_eq!(&editor, doc.updated_by());
+        assert_eq!(&author, doc.created_by());
+        assert_eq!(&touched_at, doc.updated_at());
+        assert_ne!(old_timestamp, touched_at);
+    }
+
+    #[test]
+    fn tuple_entity_test() {
+        #[derive(Debug, MutableEntity, Dummy)]
+        struct User(
+            #[id_field] Key<User, Uuid>,
+            #[created_at_field] DateTime<Utc>,
+            #[updated_at_field] DateTime<Utc>,
+            #[deleted_at_field] Option<DateTime<Utc>>,
+        );
+
+        let mut user: User = Faker.fake();
+
+        assert_eq!(&user.0, user.id());
+        assert_eq!(&user.1, user.created_at());
+        assert_eq!(&user.2, user.updated_at());
+
+        let deleted_at = *user.mark_deleted();
+
+        assert_eq!(Some(&deleted_at), user.deleted_at());
+        assert_eq!(&deleted_at, user.updated_at());
+    }
+
+    #[test]
+    fn composite_key_entity_test() {
+        #[derive(Debug, Entity, Dummy)]
+        struct Membership {
+            #[id_field]
+            key: (Uuid, u32),
+            created_at: DateTime<Utc>,
+        }
+
+        let membership: Membership = Faker.fake();
+
+        assert_eq!(&membership.key, membership.id());
+        assert_eq!(&membership.created_at, membership.created_at());
+    }
+
+    #[test]
+    fn composite_key_struct_test() {
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, Dummy)]
+        struct MembershipKey {
+            tenant: Uuid,
+            user: u32,
+        }
+
+        #[derive(Debug, Entity, Dummy)]
+        #[entity(id = "key")]
+        struct Membership {
+            key: MembershipKey,
+            created_at: DateTime<Utc>,
+        }
+
+        #[derive(Debug, Entity)]
+        struct Grant<T>(
+            #[id_field] (u8, u64),
+            #[created_at_field] DateTime<Utc>,
+            T,
+        );
+
+        let membership: Membership = Faker.fake();
+        let grant = Grant((1, 2), Utc::now(), "scope");
+
+        assert_eq!(
+            &MembershipKey {
+                tenant: membership.key.tenant,
+                user: membership.key.user,
+            },
+            membership.id()
+        );
+        assert_eq!(&membership.created_at, membership.created_at());
+        assert_eq!(&(1, 2), grant.id());
+        assert_eq!(&grant.1, grant.created_at());
+        assert_eq!("scope", grant.2);
+    }
+
+    #[test]
+    fn nested_key_entity_test() {
+        #[derive(Debug, Dummy)]
+        struct Metadata {
+            id: Key<User, Uuid>,
+            created_at: DateTime<Utc>,
+            updated_at: DateTime<Utc>,
+        }
+
+        #[derive(Debug, MutableEntity, Dummy)]
+        #[entity(
+            id = "meta.id",
+            key_type = "Key<User, Uuid>",
+            created_at = "meta.created_at",
+            updated_at = "meta.updated_at"
+        )]
+        struct User {
+            meta: Metadata,
+        }
+
+        let mut user: User = Faker.fake();
+
+        assert_eq!(&user.meta.id, user.id());
+        assert_eq!(&user.meta.created_at, user.created_at());
+
+        let touched_at = *user.touch();
+
+        assert_eq!(&touched_at, user.updated_at());
+        assert_eq!(touched_at, user.meta.updated_at);
+    }
+
+    #[test]
+    fn enum_entity_test() {
+        #[derive(Debug, MutableEntity)]
+        enum PaymentMethod {
+            Card {
+                id: Key<PaymentMethod, u32>,
+                created_at: DateTime<Utc>,
+                updated_at: DateTime<Utc>,
+                #[deleted_at_field]
+                deleted_at: Option<DateTime<Utc>>,
+            },
+            Bank(
+                #[id_field] Key<PaymentMethod, u32>,
+                #[created_at_field] DateTime<Utc>,
+                #[updated_at_field] DateTime<Utc>,
+                #[deleted_at_field] Option<DateTime<Utc>>,
+            ),
+        }
+
+        let now = Utc::now();
+        let card = PaymentMethod::Card {
+            id: Key::new(1),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+        let mut bank = PaymentMethod::Bank(Key::new(2), now, now, None);
+
+        assert_eq!(&Key::new(1), card.id());
+        assert_eq!(&now, card.created_at());
+        assert_eq!(&Key::new(2), bank.id());
+        assert!(!bank.is_deleted());
+
+        let deleted_at = *bank.mark_deleted();
+
+        assert_eq!(Some(&deleted_at), bank.deleted_at());
+        assert_eq!(&deleted_at, bank.updated_at());
+        assert!(matches!(bank, PaymentMethod::Bank(_, _, _, Some(_))));
+    }
 }
@@ -0,0 +1,352 @@
+use std::{
+    fs::{File, OpenOptions},
+    hash::Hash,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{EventStore, ExpectedVersion, InMemoryEventStore};
+use crate::domain::error::{RepoError, RepoResult};
+
+/// The store file, along with the length of its committed records.
+struct Log {
+    file: File,
+    len: u64,
+
+    /// Whether the file might hold a partially written record past `len`,
+    /// which must be truncated before writing.
+    torn: bool,
+}
+
+/// The state of a store, which is shared with the blocking tasks that write
+/// to its file.
+struct Shared<K, Ev> {
+    log: Mutex<Log>,
+    memory: InMemoryEventStore<K, Ev>,
+}
+
+/// A single line of an event store file.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<K, Ev> {
+    /// Events were appended to a stream.
+    Appended { key: K, events: Vec<Ev> },
+
+    /// A stream was deleted.
+    Deleted { key: K },
+}
+
+/// An [`EventStore`] that persists streams to a local file.
+///
+/// Every change is appended to the file as a single JSON line, and the file
+/// is replayed into memory when the store is opened, which makes this store
+/// suitable for local development and small datasets only.
+///
+/// Changes are written by blocking tasks that run to completion even if the
+/// futures that started them are dropped (e.g. on timeouts), so the file and
+/// the streams in memory never diverge. A record that was only partially
+/// written (e.g. due to a crash) is discarded when the store is opened, as
+/// its write was never acknowledged.
+pub struct FileEventStore<K, Ev> {
+    shared: Arc<Shared<K, Ev>>,
+}
+
+impl<K, Ev> FileEventStore<K, Ev>
+where
+    K: Clone + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static,
+    Ev: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Opens the store at `path`, creating the file if it does not exist.
+    ///
+    /// If the last record of the file is incomplete, it is truncated.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the store file.
+    pub fn open(path: impl AsRef<Path>) -> RepoResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(RepoError::Io)?;
+
+        let memory = InMemoryEventStore::new();
+        let mut reader = BufReader::new(&file);
+        let mut line = Vec::new();
+        let mut len = 0;
+
+        loop {
+            line.clear();
+
+            let read =
+                reader.read_until(b'\n', &mut line).map_err(RepoError::Io)?;
+
+            // records are only committed once their line ends
+            if !line.ends_with(b"\n") {
+                break;
+            }
+
+            match serde_json::from_slice(&line)
+                .map_err(|err| RepoError::Other(err.into()))?
+            {
+                | Record::Appended { key, events } => {
+                    memory.append_sync(&key, ExpectedVersion::Any, events)?;
+                }
+                | Record::Deleted { key } => memory.delete_sync(&key)?,
+            }
+
+            len += read as u64;
+        }
+
+        if !line.is_empty() {
+            file.set_len(len).map_err(RepoError::Io)?;
+        }
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                log: Mutex::new(Log {
+                    file,
+                    len,
+                    torn: false,
+                }),
+                memory,
+            }),
+        })
+    }
+
+    /// Runs `f` on the state of the store in a blocking task, which runs to
+    /// completion even if the returned future is dropped.
+    async fn run<T, F>(&self, f: F) -> RepoResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Log, &InMemoryEventStore<K, Ev>) -> RepoResult<T>
+            + Send
+            + 'static,
+    {
+        let shared = self.shared.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut log = shared.log.lock().unwrap();
+
+            f(&mut log, &shared.memory)
+        })
+        .await
+        .map_err(|err| RepoError::Other(err.into()))?
+    }
+
+    /// Appends a record to the store file, truncating whatever was written
+    /// of it if it fails.
+    fn write(log: &mut Log, record: &Record<K, Ev>) -> RepoResult<()> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|err| RepoError::Other(err.into()))?;
+
+        line.push(b'\n');
+
+        if log.torn {
+            log.file.set_len(log.len).map_err(RepoError::Io)?;
+            log.torn = false;
+        }
+
+        let written = match log.file.write_all(&line) {
+            | Ok(_) => log.file.sync_data(),
+            | Err(err) => Err(err),
+        };
+
+        if let Err(err) = written {
+            // retried before the next write if it fails
+            log.torn = log.file.set_len(log.len).is_err();
+
+            return Err(RepoError::Io(err));
+        }
+
+        log.len += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<K, Ev> EventStore for FileEventStore<K, Ev>
+where
+    K: Clone + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static,
+    Ev: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    type Event = Ev;
+    type Key = K;
+
+    async fn append(
+        &self,
+        key: &K,
+        expected: ExpectedVersion,
+        events: Vec<Ev>,
+    ) -> RepoResult<u64> {
+        let key = key.clone();
+
+        self.run(move |log, memory| {
+            let version = memory.version_sync(&key);
+
+            if !expected.is_satisfied_by(version) || events.is_empty() {
+                return memory.append_sync(&key, expected, events);
+            }
+
+            Self::write(
+                log,
+                &Record::Appended {
+                    key: key.clone(),
+                    events: events.clone(),
+                },
+            )?;
+
+            memory.append_sync(&key, ExpectedVersion::Any, events)
+        })
+        .await
+    }
+
+    async fn load(&self, key: &K) -> RepoResult<Vec<Ev>> {
+        Ok(self.shared.memory.load_sync(key, 0))
+    }
+
+    async fn load_from(&self, key: &K, version: u64) -> RepoResult<Vec<Ev>> {
+        Ok(self.shared.memory.load_sync(key, version))
+    }
+
+    async fn version(&self, key: &K) -> RepoResult<u64> {
+        Ok(self.shared.memory.version_sync(key))
+    }
+
+    async fn keys(&self) -> RepoResult<Vec<K>> {
+        Ok(self.shared.memory.keys_sync())
+    }
+
+    async fn delete(&self, key: &K) -> RepoResult<()> {
+        let key = key.clone();
+
+        self.run(move |log, memory| {
+            if memory.version_sync(&key) == 0 {
+                return memory.delete_sync(&key);
+            }
+
+            Self::write(log, &Record::Deleted { key: key.clone() })?;
+
+            memory.delete_sync(&key)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reopen_test() {
+        let path = std::env::temp_dir()
+            .join(format!("reddd-{}.jsonl", uuid::Uuid::new_v4()));
+
+        {
+            let store = FileEventStore::<u32, String>::open(&path).unwrap();
+
+            store
+                .append(&1, ExpectedVersion::NoStream, vec!["a".into()])
+                .await
+                .unwrap();
+            store
+                .append(&2, ExpectedVersion::NoStream, vec!["b".into()])
+                .await
+                .unwrap();
+            store
+                .append(&1, ExpectedVersion::Exact(1), vec!["c".into()])
+                .await
+                .unwrap();
+            store.delete(&2).await.unwrap();
+
+            assert!(store
+                .append(&1, ExpectedVersion::NoStream, vec!["d".into()])
+                .await
+                .is_err());
+        }
+
+        let store = FileEventStore::<u32, String>::open(&path).unwrap();
+
+        assert_eq!(vec!["a", "c"], store.load(&1).await.unwrap());
+        assert_eq!(2, store.version(&1).await.unwrap());
+        assert_eq!(0, store.version(&2).await.unwrap());
+        assert_eq!(vec![1], store.keys().await.unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn torn_record_test() {
+        let path = std::env::temp_dir()
+            .join(format!("reddd-{}.jsonl", uuid::Uuid::new_v4()));
+
+        {
+            let store = FileEventStore::<u32, String>::open(&path).unwrap();
+
+            store
+                .append(&1, ExpectedVersion::NoStream, vec!["a".into()])
+                .await
+                .unwrap();
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+
+        std::io::Write::write_all(&mut file, br#"{"type":"appended","ke"#)
+            .unwrap();
+
+        {
+            let store = FileEventStore::<u32, String>::open(&path).unwrap();
+
+            assert_eq!(vec!["a"], store.load(&1).await.unwrap());
+
+            store
+                .append(&1, ExpectedVersion::Exact(1), vec!["b".into()])
+                .await
+                .unwrap();
+        }
+
+        let store = FileEventStore::<u32, String>::open(&path).unwrap();
+
+        assert_eq!(vec!["a", "b"], store.load(&1).await.unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropped_append_test() {
+        let path = std::env::temp_dir()
+            .join(format!("reddd-{}.jsonl", uuid::Uuid::new_v4()));
+
+        {
+            let store = FileEventStore::<u32, String>::open(&path).unwrap();
+            let events = (0..1000).map(|i| i.to_string()).collect();
+
+            // dropped as soon as it is pending
+            let _ = tokio::time::timeout(
+                std::time::Duration::ZERO,
+                store.append(&1, ExpectedVersion::NoStream, events),
+            )
+            .await;
+
+            store
+                .append(&1, ExpectedVersion::Any, vec!["last".into()])
+                .await
+                .unwrap();
+
+            assert_eq!(1001, store.version(&1).await.unwrap());
+        }
+
+        let store = FileEventStore::<u32, String>::open(&path).unwrap();
+        let events = store.load(&1).await.unwrap();
+
+        assert_eq!(1001, events.len());
+        assert_eq!(Some(&"last".to_string()), events.last());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, hash::Hash, sync::Mutex};
+
+use super::{EventStore, ExpectedVersion};
+use crate::domain::error::{RepoError, RepoResult};
+
+/// An [`EventStore`] that keeps streams in memory.
+///
+/// This store is mostly useful for tests and local development, as events are
+/// lost once the store is dropped.
+pub struct InMemoryEventStore<K, Ev> {
+    streams: Mutex<HashMap<K, Vec<Ev>>>,
+}
+
+impl<K, Ev> InMemoryEventStore<K, Ev> {
+    /// Creates a new empty store.
+    pub fn new() -> Self {
+        Self {
+            streams: Default::default(),
+        }
+    }
+}
+
+impl<K, Ev> Default for InMemoryEventStore<K, Ev> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, Ev> InMemoryEventStore<K, Ev>
+where
+    K: Clone + Eq + Hash,
+    Ev: Clone,
+{
+    pub(super) fn append_sync(
+        &self,
+        key: &K,
+        expected: ExpectedVersion,
+        events: Vec<Ev>,
+    ) -> RepoResult<u64> {
+        let mut streams = self.streams.lock().unwrap();
+        let version = streams.get(key).map_or(0, |s| s.len() as u64);
+
+        if !expected.is_satisfied_by(version) {
+            return Err(RepoError::PreconditionFailed(format!(
+                "expected stream version to be {expected:?}, found {version}"
+            )));
+        }
+
+        if events.is_empty() {
+            return Ok(version);
+        }
+
+        let stream = streams.entry(key.clone()).or_default();
+
+        stream.extend(events);
+
+        Ok(stream.len() as u64)
+    }
+
+    pub(super) fn load_sync(&self, key: &K, version: u64) -> Vec<Ev> {
+        let streams = self.streams.lock().unwrap();
+
+        streams.get(key).map_or_else(Vec::new, |s| {
+            s.iter().skip(version as usize).cloned().collect()
+        })
+    }
+
+    pub(super) fn version_sync(&self, key: &K) -> u64 {
+        let streams = self.streams.lock().unwrap();
+
+        streams.get(key).map_or(0, |s| s.len() as u64)
+    }
+
+    pub(super) fn keys_sync(&self) -> Vec<K> {
+        self.streams.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub(super) fn delete_sync(&self, key: &K) -> RepoResult<()> {
+        match self.streams.lock().unwrap().remove(key) {
+            | Some(_) => Ok(()),
+            | None => Err(RepoError::NotFound("stream does not exist".into())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<K, Ev> EventStore for InMemoryEventStore<K, Ev>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+    Ev: Clone + Send + Sync,
+{
+    type Event = Ev;
+    type Key = K;
+
+    async fn append(
+        &self,
+        key: &K,
+        expected: ExpectedVersion,
+        events: Vec<Ev>,
+    ) -> RepoResult<u64> {
+        self.append_sync(key, expected, events)
+    }
+
+    async fn load(&self, key: &K) -> RepoResult<Vec<Ev>> {
+        Ok(self.load_sync(key, 0))
+    }
+
+    async fn load_from(&self, key: &K, version: u64) -> RepoResult<Vec<Ev>> {
+        Ok(self.load_sync(key, version))
+    }
+
+    async fn version(&self, key: &K) -> RepoResult<u64> {
+        Ok(self.version_sync(key))
+    }
+
+    async fn keys(&self) -> RepoResult<Vec<K>> {
+        Ok(self.keys_sync())
+    }
+
+    async fn delete(&self, key: &K) -> RepoResult<()> {
+        self.delete_sync(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn append_load_test() {
+        let store = InMemoryEventStore::<u32, &str>::new();
+
+        assert_eq!(0, store.version(&1).await.unwrap());
+        assert!(store.load(&1).await.unwrap().is_empty());
+
+        assert_eq!(
+            2,
+            store
+                .append(&1, ExpectedVersion::NoStream, vec!["a", "b"])
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            3,
+            store
+                .append(&1, ExpectedVersion::Exact(2), vec!["c"])
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(vec!["a", "b", "c"], store.load(&1).await.unwrap());
+        assert_eq!(vec!["c"], store.load_from(&1, 2).await.unwrap());
+        assert!(store.load_from(&1, 5).await.unwrap().is_empty());
+        assert_eq!(3, store.version(&1).await.unwrap());
+        assert_eq!(vec![1], store.keys().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn expected_version_test() {
+        let store = InMemoryEventStore::<u32, &str>::new();
+
+        for expected in
+            [ExpectedVersion::StreamExists, ExpectedVersion::Exact(1)]
+        {
+            assert!(matches!(
+                store.append(&1, expected, vec!["a"]).await,
+                Err(RepoError::PreconditionFailed(_))
+            ));
+        }
+
+        store
+            .append(&1, ExpectedVersion::Any, vec!["a"])
+            .await
+            .unwrap();
+
+        for expected in [ExpectedVersion::NoStream, ExpectedVersion::Exact(0)] {
+            assert!(matches!(
+                store.append(&1, expected, vec!["b"]).await,
+                Err(RepoError::PreconditionFailed(_))
+            ));
+        }
+
+        assert_eq!(vec!["a"], store.load(&1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn delete_test() {
+        let store = InMemoryEventStore::<u32, &str>::new();
+
+        store
+            .append(&1, ExpectedVersion::Any, vec!["a"])
+            .await
+            .unwrap();
+        store.delete(&1).await.unwrap();
+
+        assert_eq!(0, store.version(&1).await.unwrap());
+        assert!(matches!(
+            store.delete(&1).await,
+            Err(RepoError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use super::{error::RepoResult, MutableEntity};
+
+mod file;
+mod memory;
+mod repo;
+mod snapshot;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub use file::*;
+pub use memory::*;
+pub use repo::*;
+pub use snapshot::*;
+
+/// A trait to be implemented by entities which state is persisted as a
+/// stream of events, rather than as the state itself.
+///
+/// Changes to the state of the entity are recorded as events, which are
+/// [applied](EventSourced::apply) on the state and kept pending until
+/// persisted. The state is rebuilt later by replaying the persisted events.
+///
+/// # Example
+///
+/// ```ignore
+/// enum AccountEvent {
+///     Opened { id: Key<Account, u32>, at: DateTime<Utc> },
+///     Deposited { amount: u64, at: DateTime<Utc> },
+/// }
+///
+/// impl Account {
+///     pub fn deposit(&mut self, amount: u64) {
+///         self.record(AccountEvent::Deposited { amount, at: Utc::now() });
+///     }
+///
+///     fn record(&mut self, event: AccountEvent) {
+///         self.apply(&event);
+///         self.pending_events.push(event);
+///     }
+/// }
+///
+/// impl EventSourced for Account {
+///     type Event = AccountEvent;
+///
+///     fn from_first_event(event: &Self::Event) -> Self {
+///         // --snip--
+///     }
+///
+///     fn apply(&mut self, event: &Self::Event) {
+///         match *event {
+///             AccountEvent::Deposited { amount, at } => {
+///                 self.balance += amount;
+///                 self.updated_at = at;
+///             }
+///             // --snip--
+///         }
+///     }
+///
+///     fn take_pending_events(&mut self) -> Vec<Self::Event> {
+///         std::mem::take(&mut self.pending_events)
+///     }
+/// }
+/// ```
+pub trait EventSourced: MutableEntity + Sized {
+    /// The type of the events that change the state of the entity.
+    type Event: Clone + Send + Sync;
+
+    /// Creates the initial state of the entity from the first event of its
+    /// stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The first event of the stream.
+    fn from_first_event(event: &Self::Event) -> Self;
+
+    /// Applies an event on the state of the entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to apply.
+    fn apply(&mut self, event: &Self::Event);
+
+    /// Moves the events that were recorded on the entity, but were not
+    /// persisted yet, out of `self`.
+    fn take_pending_events(&mut self) -> Vec<Self::Event>;
+
+    /// Rebuilds an entity by replaying a stream of events, or returns
+    /// [`None`] if the stream is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events of the stream, in order.
+    fn replay<'a>(
+        events: impl IntoIterator<Item = &'a Self::Event>,
+    ) -> Option<Self>
+    where
+        Self::Event: 'a,
+    {
+        let mut events = events.into_iter();
+        let mut entity = Self::from_first_event(events.next()?);
+
+        events.for_each(|e| entity.apply(e));
+
+        Some(entity)
+    }
+}
+
+/// An enumeration of conditions on the version of a stream, which is the
+/// number of events in it, to be checked before appending events to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpectedVersion {
+    /// The stream can be in any state.
+    Any,
+
+    /// The stream must not exist.
+    NoStream,
+
+    /// The stream must exist.
+    StreamExists,
+
+    /// The stream must be at exactly this version.
+    Exact(u64),
+}
+
+impl ExpectedVersion {
+    /// Checks whether a stream at `version` satisfies this expectation.
+    pub fn is_satisfied_by(&self, version: u64) -> bool {
+        match *self {
+            | ExpectedVersion::Any => true,
+            | ExpectedVersion::NoStream => version == 0,
+            | ExpectedVersion::StreamExists => version > 0,
+            | ExpectedVersion::Exact(expected) => version == expected,
+        }
+    }
+}
+
+/// A trait to be implemented by event stores, which persist streams of
+/// events, each identified by the key of the entity it belongs to.
+#[async_trait::async_trait]
+pub trait EventStore: Sync {
+    /// The type of the keys that identify streams.
+    type Key: Send + Sync;
+
+    /// The type of the stored events.
+    type Event: Send + Sync;
+
+    /// Appends events to a stream, creating it if it does not exist, and
+    /// returns the new version of the stream.
+    ///
+    /// If the stream does not satisfy `expected`,
+    /// [`RepoError::PreconditionFailed`](super::error::RepoError::PreconditionFailed)
+    /// is returned, and no events are appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    /// * `expected` - The condition the stream must satisfy.
+    /// * `events` - The events to append, in order.
+    async fn append(
+        &self,
+        key: &Self::Key,
+        expected: ExpectedVersion,
+        events: Vec<Self::Event>,
+    ) -> RepoResult<u64>;
+
+    /// Loads all events of a stream, in order, or an empty vector if the
+    /// stream does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn load(&self, key: &Self::Key) -> RepoResult<Vec<Self::Event>>;
+
+    /// Loads the events of a stream that were appended after it reached
+    /// `version`, in order.
+    ///
+    /// The default implementation loads the whole stream and skips the
+    /// leading events, thus stores should override it when they can load
+    /// partial streams more efficiently.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    /// * `version` - The version to load events after.
+    async fn load_from(
+        &self,
+        key: &Self::Key,
+        version: u64,
+    ) -> RepoResult<Vec<Self::Event>> {
+        let mut events = self.load(key).await?;
+
+        events.drain(..events.len().min(version as usize));
+
+        Ok(events)
+    }
+
+    /// Gets the version of a stream, which is `0` if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn version(&self, key: &Self::Key) -> RepoResult<u64>;
+
+    /// Gets the keys of all existing streams.
+    async fn keys(&self) -> RepoResult<Vec<Self::Key>>;
+
+    /// Deletes a stream with all of its events.
+    ///
+    /// If the stream does not exist,
+    /// [`RepoError::NotFound`](super::error::RepoError::NotFound) is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn delete(&self, key: &Self::Key) -> RepoResult<()>;
+}
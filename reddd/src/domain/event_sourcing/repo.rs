@@ -0,0 +1,426 @@
+use std::marker::PhantomData;
+
+use super::{
+    EventSourced,
+    EventStore,
+    ExpectedVersion,
+    NoSnapshots,
+    SnapshotPolicy,
+    SnapshotStore,
+    SnapshotStrategy,
+    Snapshotting,
+};
+use crate::domain::{
+    error::{RepoError, RepoResult},
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+
+/// A repository of [`EventSourced`] entities, which persists the pending
+/// events of entities to an [`EventStore`] and rebuilds entities by replaying
+/// their streams.
+///
+/// Entities are written by appending their
+/// [pending events](EventSourced::take_pending_events) to the stream of their
+/// key, thus written entities must have recorded their changes as events.
+///
+/// # Example
+///
+/// ```ignore
+/// let accounts_repo = EventSourcedRepo::<Account, _>::new(
+///     FileEventStore::open("accounts.jsonl")?,
+/// );
+///
+/// let mut account = accounts_repo.get(&account_id).await?;
+///
+/// account.deposit(100);
+/// accounts_repo.update(account).await?;
+/// ```
+pub struct EventSourcedRepo<E, S, N = NoSnapshots> {
+    store: S,
+    snapshots: N,
+    _entity: PhantomData<fn() -> E>,
+}
+
+impl<E, S> EventSourcedRepo<E, S>
+where
+    E: EventSourced,
+    S: EventStore<Key = E::Key, Event = E::Event>,
+{
+    /// Creates a new repository on top of an event store, which replays
+    /// whole streams to load entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to persist events to.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            snapshots: NoSnapshots,
+            _entity: PhantomData,
+        }
+    }
+
+    /// Makes the repository load entities from their latest snapshots, plus
+    /// the events appended since.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to keep snapshots in.
+    /// * `policy` - The policy to decide when to take new snapshots.
+    pub fn with_snapshots<N>(
+        self,
+        store: N,
+        policy: SnapshotPolicy,
+    ) -> EventSourcedRepo<E, S, Snapshotting<N>>
+    where
+        N: SnapshotStore<Key = E::Key>,
+    {
+        EventSourcedRepo {
+            store: self.store,
+            snapshots: Snapshotting::new(store, policy),
+            _entity: PhantomData,
+        }
+    }
+}
+
+impl<E, S, N> EventSourcedRepo<E, S, N>
+where
+    E: EventSourced,
+    S: EventStore<Key = E::Key, Event = E::Event>,
+    N: SnapshotStrategy<E>,
+{
+    /// Gets a reference to the underlying event store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Gets a reference to the strategy used to rebuild entities.
+    pub fn snapshots(&self) -> &N {
+        &self.snapshots
+    }
+
+    /// Rebuilds an entity from its stream, along with the stream version.
+    async fn rebuild(&self, key: &E::Key) -> RepoResult<(E, u64)> {
+        self.snapshots
+            .rebuild(&self.store, key)
+            .await?
+            .ok_or_else(|| RepoError::NotFound("entity does not exist".into()))
+    }
+
+    /// Lets the snapshot strategy observe a written stream.
+    ///
+    /// Failures are not returned, as the events of the entity are committed
+    /// already, and loads fall back to older snapshots anyway.
+    async fn saved(&self, key: &E::Key, version: u64) {
+        if let Err(_err) = self.snapshots.saved(&self.store, key, version).await
+        {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_err, version, "failed to take snapshot");
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E, S, N> ReadRepo for EventSourcedRepo<E, S, N>
+where
+    E: EventSourced + Send + Sync,
+    E::Key: Clone + PartialOrd + Send + Sync,
+    S: EventStore<Key = E::Key, Event = E::Event>,
+    N: SnapshotStrategy<E>,
+{
+    type Entity = E;
+
+    async fn get(&self, key: &E::Key) -> RepoResult<E> {
+        self.rebuild(key).await.map(|(entity, _)| entity)
+    }
+
+    /// Gets a page of items using passed pagination parameters.
+    ///
+    /// As event stores are not indexed by creation timestamps, all streams
+    /// are replayed to build a single page.
+    async fn get_page(&self, params: Pagination<E>) -> RepoResult<Vec<E>> {
+        let mut entities = Vec::new();
+
+        for key in self.store.keys().await? {
+            let (entity, _) = self.rebuild(&key).await?;
+
+            if (entity.created_at(), entity.id())
+                < (&params.before_timestamp, &params.before_key)
+            {
+                entities.push(entity);
+            }
+        }
+
+        entities.sort_by(|a, b| {
+            (b.created_at(), b.id())
+                .partial_cmp(&(a.created_at(), a.id()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        entities.truncate(params.page_size);
+
+        Ok(entities)
+    }
+
+    async fn exists(&self, key: &E::Key) -> RepoResult<bool> {
+        Ok(self.store.version(key).await? > 0)
+    }
+}
+
+#[async_trait::async_trait]
+impl<E, S, N> WriteRepo for EventSourcedRepo<E, S, N>
+where
+    E: EventSourced + Send + Sync,
+    E::Key: Sync,
+    S: EventStore<Key = E::Key, Event = E::Event>,
+    N: SnapshotStrategy<E>,
+{
+    type Entity = E;
+
+    async fn add(&self, mut item: E) -> RepoResult<E> {
+        let events = item.take_pending_events();
+
+        if events.is_empty() {
+            return Err(RepoError::InvalidParameter(
+                "entity has no pending events".into(),
+            ));
+        }
+
+        match self
+            .store
+            .append(item.id(), ExpectedVersion::NoStream, events)
+            .await
+        {
+            | Ok(version) => {
+                self.saved(item.id(), version).await;
+
+                Ok(item)
+            }
+            | Err(RepoError::PreconditionFailed(_)) => {
+                Err(RepoError::DuplicateValue("entity already exists".into()))
+            }
+            | Err(err) => Err(err),
+        }
+    }
+
+    async fn update(&self, mut item: E) -> RepoResult<E> {
+        let events = item.take_pending_events();
+
+        match self
+            .store
+            .append(item.id(), ExpectedVersion::StreamExists, events)
+            .await
+        {
+            | Ok(version) => {
+                self.saved(item.id(), version).await;
+
+                Ok(item)
+            }
+            | Err(RepoError::PreconditionFailed(_)) => {
+                Err(RepoError::NotFound("entity does not exist".into()))
+            }
+            | Err(err) => Err(err),
+        }
+    }
+
+    async fn upsert(&self, mut item: E) -> RepoResult<Upserted<E>> {
+        let events = item.take_pending_events();
+        let count = events.len() as u64;
+
+        let version = self
+            .store
+            .append(item.id(), ExpectedVersion::Any, events)
+            .await?;
+
+        if version == 0 {
+            return Err(RepoError::InvalidParameter(
+                "entity has no pending events".into(),
+            ));
+        }
+
+        self.saved(item.id(), version).await;
+
+        match version == count {
+            | true => Ok(Upserted::Inserted(item)),
+            | false => Ok(Upserted::Updated(item)),
+        }
+    }
+
+    async fn update_if(
+        &self,
+        mut item: E,
+        predicate: &Predicate<'_, E>,
+    ) -> RepoResult<E> {
+        let (stored, version) = self.rebuild(item.id()).await?;
+
+        if !predicate(&stored) {
+            return Err(RepoError::PreconditionFailed(
+                "stored entity does not satisfy the predicate".into(),
+            ));
+        }
+
+        let events = item.take_pending_events();
+        let version = self
+            .store
+            .append(item.id(), ExpectedVersion::Exact(version), events)
+            .await?;
+
+        self.saved(item.id(), version).await;
+
+        Ok(item)
+    }
+
+    /// Removes an item from the data repository, by deleting its whole
+    /// stream of events, along with its snapshot.
+    async fn remove(&self, key: &E::Key) -> RepoResult<()> {
+        self.store.delete(key).await?;
+        self.snapshots.forget(key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::domain::{
+        event_sourcing::{
+            testing::{Account, AccountEvent},
+            InMemoryEventStore,
+        },
+        Entity,
+        Key,
+        MutableEntity,
+        ValueType,
+    };
+
+    type AccountsRepo = EventSourcedRepo<
+        Account,
+        InMemoryEventStore<Key<Account, u32>, AccountEvent>,
+    >;
+
+    fn repo() -> AccountsRepo {
+        EventSourcedRepo::new(InMemoryEventStore::new())
+    }
+
+    #[tokio::test]
+    async fn add_get_test() {
+        let repo = repo();
+        let mut account = Account::open(1);
+
+        account.deposit(10);
+        account.deposit(5);
+
+        let account = repo.add(account).await.unwrap();
+        let stored = repo.get(account.id()).await.unwrap();
+
+        assert!(account.pending.is_empty());
+        assert_eq!(3, repo.store().version(account.id()).await.unwrap());
+        assert_eq!(15, stored.balance);
+        assert_eq!(account.created_at(), stored.created_at());
+        assert_eq!(account.updated_at(), stored.updated_at());
+        assert!(repo.exists(account.id()).await.unwrap());
+
+        assert!(matches!(
+            repo.add(Account::open(1)).await,
+            Err(RepoError::DuplicateValue(_))
+        ));
+        assert!(matches!(
+            repo.get(&Key::new(2)).await,
+            Err(RepoError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn update_test() {
+        let repo = repo();
+        let mut account = repo.add(Account::open(1)).await.unwrap();
+
+        account.deposit(7);
+        repo.update(account).await.unwrap();
+
+        let mut stored = repo.get(&Key::new(1)).await.unwrap();
+
+        assert_eq!(7, stored.balance);
+
+        stored.deposit(3);
+        repo.update(stored).await.unwrap();
+
+        assert_eq!(10, repo.get(&Key::new(1)).await.unwrap().balance);
+        assert!(matches!(
+            repo.update(Account::open(2)).await,
+            Err(RepoError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn upsert_test() {
+        let repo = repo();
+        let mut account = Account::open(1);
+
+        account.deposit(1);
+
+        let upserted = repo.upsert(account).await.unwrap();
+
+        assert!(upserted.is_inserted());
+
+        let mut account = upserted.into_inner();
+
+        account.deposit(2);
+
+        assert!(repo.upsert(account).await.unwrap().is_updated());
+        assert_eq!(3, repo.get(&Key::new(1)).await.unwrap().balance);
+    }
+
+    #[tokio::test]
+    async fn update_if_test() {
+        let repo = repo();
+        let account = repo.add(Account::open(1)).await.unwrap();
+        let last_seen = *account.updated_at();
+
+        let mut first = repo.get(account.id()).await.unwrap();
+        let mut second = repo.get(account.id()).await.unwrap();
+
+        first.deposit(1);
+        second.deposit(2);
+
+        repo.update_if(first, &|s| s.updated_at() == &last_seen)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            repo.update_if(second, &|s| s.updated_at() == &last_seen)
+                .await,
+            Err(RepoError::PreconditionFailed(_))
+        ));
+        assert_eq!(1, repo.get(account.id()).await.unwrap().balance);
+    }
+
+    #[tokio::test]
+    async fn get_page_remove_test() {
+        let repo = repo();
+
+        for id in 1..=3 {
+            repo.add(Account::open(id)).await.unwrap();
+        }
+
+        repo.remove(&Key::new(2)).await.unwrap();
+
+        let page = repo
+            .get_page(Pagination {
+                before_key: Key::new(0),
+                before_timestamp: Utc::now(),
+                page_size: 10,
+            })
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = page.iter().map(|a| *a.id().as_inner()).collect();
+
+        assert_eq!(vec![3, 1], ids);
+        assert!(!repo.exists(&Key::new(2)).await.unwrap());
+    }
+}
@@ -0,0 +1,587 @@
+use std::{collections::HashMap, hash::Hash, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{EventSourced, EventStore};
+use crate::domain::error::{RepoError, RepoResult};
+
+/// A trait to be implemented by [`EventSourced`] entities which state can be
+/// snapshotted, to avoid replaying their whole streams when loaded.
+///
+/// Snapshots are stored in the serde representation of the entity, thus
+/// [`SNAPSHOT_VERSION`](Snapshottable::SNAPSHOT_VERSION) must be bumped
+/// whenever that representation changes. Snapshots taken with a different
+/// version are ignored, and the entity is rebuilt from its whole stream,
+/// while snapshots that cannot be deserialized despite having the same
+/// version fail loading the entity.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(MutableEntity, Serialize, Deserialize)]
+/// struct Account {
+///     // --snip--
+///     #[serde(skip)]
+///     pending_events: Vec<AccountEvent>,
+/// }
+///
+/// impl Snapshottable for Account {
+///     const SNAPSHOT_VERSION: u32 = 2;
+/// }
+/// ```
+pub trait Snapshottable: EventSourced + Serialize + DeserializeOwned {
+    /// The version of the serde representation of the entity.
+    const SNAPSHOT_VERSION: u32;
+}
+
+/// A struct that holds the state of an entity at a specific version of its
+/// stream.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The version of the stream the state was taken at.
+    pub version: u64,
+
+    /// The [`Snapshottable::SNAPSHOT_VERSION`] of the entity the snapshot was
+    /// taken with.
+    pub schema_version: u32,
+
+    /// The time the snapshot was taken at.
+    pub taken_at: DateTime<Utc>,
+
+    /// The serialized state of the entity.
+    pub state: serde_json::Value,
+}
+
+impl Snapshot {
+    /// Takes a snapshot of an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The entity to take the snapshot of.
+    /// * `version` - The version of the stream of the entity.
+    pub fn take<E: Snapshottable>(
+        entity: &E,
+        version: u64,
+    ) -> RepoResult<Self> {
+        Ok(Self {
+            version,
+            schema_version: E::SNAPSHOT_VERSION,
+            taken_at: Utc::now(),
+            state: serde_json::to_value(entity)
+                .map_err(|err| RepoError::Other(err.into()))?,
+        })
+    }
+
+    /// Restores the entity the snapshot was taken of, or returns [`None`] if
+    /// the snapshot was taken with a different
+    /// [`SNAPSHOT_VERSION`](Snapshottable::SNAPSHOT_VERSION).
+    ///
+    /// If the state cannot be deserialized despite having the same version,
+    /// [`RepoError::Other`] is returned, as the version was likely not
+    /// bumped when the representation of the entity changed.
+    pub fn restore<E: Snapshottable>(&self) -> RepoResult<Option<E>> {
+        if self.schema_version != E::SNAPSHOT_VERSION {
+            return Ok(None);
+        }
+
+        serde_json::from_value(self.state.clone())
+            .map(Some)
+            .map_err(|err| RepoError::Other(err.into()))
+    }
+}
+
+/// An enumeration of policies to decide when to take a new snapshot of an
+/// entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotPolicy {
+    /// Take a snapshot once this many events were appended since the last
+    /// one.
+    EveryEvents(u64),
+
+    /// Take a snapshot once this much time passed since the last one, and
+    /// events were appended since.
+    Every(Duration),
+}
+
+impl SnapshotPolicy {
+    /// Checks whether a new snapshot is due for a stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `last` - The last snapshot taken of the stream, if any.
+    /// * `version` - The current version of the stream.
+    pub fn is_due(&self, last: Option<&Snapshot>, version: u64) -> bool {
+        let last_version = last.map_or(0, |s| s.version);
+
+        if version <= last_version {
+            return false;
+        }
+
+        match *self {
+            | SnapshotPolicy::EveryEvents(count) => {
+                version - last_version >= count
+            }
+            | SnapshotPolicy::Every(interval) => last.is_none_or(|s| {
+                (Utc::now() - s.taken_at)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= interval)
+            }),
+        }
+    }
+}
+
+/// A trait to be implemented by snapshot stores, which keep the latest
+/// snapshot of each stream.
+#[async_trait::async_trait]
+pub trait SnapshotStore: Sync {
+    /// The type of the keys that identify streams.
+    type Key: Send + Sync;
+
+    /// Loads the latest snapshot of a stream, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn load(&self, key: &Self::Key) -> RepoResult<Option<Snapshot>>;
+
+    /// Saves a snapshot of a stream, replacing the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    /// * `snapshot` - The snapshot to save.
+    async fn save(&self, key: &Self::Key, snapshot: Snapshot)
+        -> RepoResult<()>;
+
+    /// Deletes the snapshot of a stream, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn delete(&self, key: &Self::Key) -> RepoResult<()>;
+}
+
+/// A [`SnapshotStore`] that keeps snapshots in memory.
+pub struct InMemorySnapshotStore<K> {
+    snapshots: Mutex<HashMap<K, Snapshot>>,
+}
+
+impl<K> InMemorySnapshotStore<K> {
+    /// Creates a new empty store.
+    pub fn new() -> Self {
+        Self {
+            snapshots: Default::default(),
+        }
+    }
+}
+
+impl<K> Default for InMemorySnapshotStore<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl<K> SnapshotStore for InMemorySnapshotStore<K>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+{
+    type Key = K;
+
+    async fn load(&self, key: &K) -> RepoResult<Option<Snapshot>> {
+        Ok(self.snapshots.lock().unwrap().get(key).cloned())
+    }
+
+    async fn save(&self, key: &K, snapshot: Snapshot) -> RepoResult<()> {
+        self.snapshots.lock().unwrap().insert(key.clone(), snapshot);
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &K) -> RepoResult<()> {
+        self.snapshots.lock().unwrap().remove(key);
+
+        Ok(())
+    }
+}
+
+/// A trait to be implemented by strategies of rebuilding entities from their
+/// streams, which is used by
+/// [`EventSourcedRepo`](super::EventSourcedRepo) to support snapshots.
+#[async_trait::async_trait]
+pub trait SnapshotStrategy<E: EventSourced>: Sync {
+    /// Rebuilds an entity from its stream, along with the stream version, or
+    /// returns [`None`] if the stream does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The store of the stream.
+    /// * `key` - The key of the stream.
+    async fn rebuild<S>(
+        &self,
+        events: &S,
+        key: &E::Key,
+    ) -> RepoResult<Option<(E, u64)>>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>;
+
+    /// Observes a stream that was just written to, which brought it to
+    /// `version`, e.g. to take a snapshot of it.
+    ///
+    /// Snapshots are taken of entities rebuilt from `events`, rather than of
+    /// the written entities, which might miss events appended by other
+    /// writers in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The store of the stream.
+    /// * `key` - The key of the stream.
+    /// * `version` - The version of the stream after the write.
+    async fn saved<S>(
+        &self,
+        events: &S,
+        key: &E::Key,
+        version: u64,
+    ) -> RepoResult<()>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>;
+
+    /// Forgets everything kept about a deleted stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the stream.
+    async fn forget(&self, key: &E::Key) -> RepoResult<()>;
+}
+
+/// A [`SnapshotStrategy`] that always replays whole streams.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoSnapshots;
+
+#[async_trait::async_trait]
+impl<E> SnapshotStrategy<E> for NoSnapshots
+where
+    E: EventSourced + Send + Sync,
+    E::Key: Sync,
+{
+    async fn rebuild<S>(
+        &self,
+        events: &S,
+        key: &E::Key,
+    ) -> RepoResult<Option<(E, u64)>>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>,
+    {
+        let events = events.load(key).await?;
+
+        Ok(E::replay(&events).map(|entity| (entity, events.len() as u64)))
+    }
+
+    async fn saved<S>(
+        &self,
+        _events: &S,
+        _key: &E::Key,
+        _version: u64,
+    ) -> RepoResult<()>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>,
+    {
+        Ok(())
+    }
+
+    async fn forget(&self, _key: &E::Key) -> RepoResult<()> {
+        Ok(())
+    }
+}
+
+/// A [`SnapshotStrategy`] that loads entities from their latest snapshots
+/// plus the events appended since, and takes new snapshots as entities are
+/// written according to a [`SnapshotPolicy`].
+pub struct Snapshotting<N> {
+    store: N,
+    policy: SnapshotPolicy,
+}
+
+impl<N> Snapshotting<N> {
+    /// Creates a new snapshotting strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to keep snapshots in.
+    /// * `policy` - The policy to decide when to take new snapshots.
+    pub fn new(store: N, policy: SnapshotPolicy) -> Self {
+        Self { store, policy }
+    }
+
+    /// Gets a reference to the underlying snapshot store.
+    pub fn store(&self) -> &N {
+        &self.store
+    }
+
+    /// Gets the policy to decide when to take new snapshots.
+    pub fn policy(&self) -> SnapshotPolicy {
+        self.policy
+    }
+}
+
+#[async_trait::async_trait]
+impl<E, N> SnapshotStrategy<E> for Snapshotting<N>
+where
+    E: Snapshottable + Send + Sync,
+    E::Key: Sync,
+    N: SnapshotStore<Key = E::Key>,
+{
+    async fn rebuild<S>(
+        &self,
+        events: &S,
+        key: &E::Key,
+    ) -> RepoResult<Option<(E, u64)>>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>,
+    {
+        let snapshot = self.store.load(key).await?;
+
+        restore(snapshot.as_ref(), events, key).await
+    }
+
+    async fn saved<S>(
+        &self,
+        events: &S,
+        key: &E::Key,
+        version: u64,
+    ) -> RepoResult<()>
+    where
+        S: EventStore<Key = E::Key, Event = E::Event>,
+    {
+        let last = self
+            .store
+            .load(key)
+            .await?
+            .filter(|s| s.schema_version == E::SNAPSHOT_VERSION);
+
+        if !self.policy.is_due(last.as_ref(), version) {
+            return Ok(());
+        }
+
+        if let Some((entity, version)) =
+            restore::<E, S>(last.as_ref(), events, key).await?
+        {
+            self.store
+                .save(key, Snapshot::take(&entity, version)?)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn forget(&self, key: &E::Key) -> RepoResult<()> {
+        self.store.delete(key).await
+    }
+}
+
+/// Restores an entity from `snapshot`, if any, plus the events appended
+/// since, or replays its whole stream otherwise, along with the stream
+/// version.
+async fn restore<E, S>(
+    snapshot: Option<&Snapshot>,
+    events: &S,
+    key: &E::Key,
+) -> RepoResult<Option<(E, u64)>>
+where
+    E: Snapshottable,
+    S: EventStore<Key = E::Key, Event = E::Event>,
+{
+    let restored = match snapshot {
+        | Some(snapshot) => snapshot
+            .restore::<E>()?
+            .map(|entity| (entity, snapshot.version)),
+        | None => None,
+    };
+
+    let (entity, version) = match restored {
+        | Some((mut entity, version)) => {
+            let trailing = events.load_from(key, version).await?;
+
+            trailing.iter().for_each(|e| entity.apply(e));
+
+            (entity, version + trailing.len() as u64)
+        }
+        | None => {
+            let events = events.load(key).await?;
+
+            match E::replay(&events) {
+                | Some(entity) => (entity, events.len() as u64),
+                | None => return Ok(None),
+            }
+        }
+    };
+
+    Ok(Some((entity, version)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        event_sourcing::{
+            testing::Account,
+            EventSourcedRepo,
+            InMemoryEventStore,
+        },
+        Key,
+        ReadRepo,
+        WriteRepo,
+    };
+
+    #[test]
+    fn policy_test() {
+        let snapshot = |version, age| Snapshot {
+            version,
+            schema_version: 1,
+            taken_at: Utc::now() - chrono::Duration::seconds(age),
+            state: serde_json::Value::Null,
+        };
+
+        let every_events = SnapshotPolicy::EveryEvents(3);
+
+        assert!(!every_events.is_due(None, 2));
+        assert!(every_events.is_due(None, 3));
+        assert!(!every_events.is_due(Some(&snapshot(3, 0)), 5));
+        assert!(every_events.is_due(Some(&snapshot(3, 0)), 6));
+
+        let every = SnapshotPolicy::Every(Duration::from_secs(60));
+
+        assert!(!every.is_due(None, 0));
+        assert!(every.is_due(None, 1));
+        assert!(!every.is_due(Some(&snapshot(1, 10)), 2));
+        assert!(!every.is_due(Some(&snapshot(2, 120)), 2));
+        assert!(every.is_due(Some(&snapshot(1, 120)), 2));
+    }
+
+    #[tokio::test]
+    async fn snapshot_rebuild_test() {
+        let repo =
+            EventSourcedRepo::<Account, _>::new(InMemoryEventStore::new())
+                .with_snapshots(
+                    InMemorySnapshotStore::new(),
+                    SnapshotPolicy::EveryEvents(3),
+                );
+        let key = Key::new(1);
+        let mut account = Account::open(1);
+
+        account.deposit(1);
+        account.deposit(2);
+        repo.add(account).await.unwrap();
+
+        let snapshot = repo.snapshots().store().load(&key).await.unwrap();
+
+        assert_eq!(Some(3), snapshot.as_ref().map(|s| s.version));
+        assert_eq!(3, repo.get(&key).await.unwrap().balance);
+
+        let mut account = repo.get(&key).await.unwrap();
+
+        account.deposit(4);
+        repo.update(account).await.unwrap();
+
+        let account = repo.get(&key).await.unwrap();
+        let snapshot = repo.snapshots().store().load(&key).await.unwrap();
+
+        assert_eq!(7, account.balance);
+        assert!(account.pending.is_empty());
+        assert_eq!(Some(3), snapshot.map(|s| s.version));
+
+        repo.remove(&key).await.unwrap();
+
+        assert!(repo.snapshots().store().load(&key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn concurrent_writers_test() {
+        let repo =
+            EventSourcedRepo::<Account, _>::new(InMemoryEventStore::new())
+                .with_snapshots(
+                    InMemorySnapshotStore::new(),
+                    SnapshotPolicy::EveryEvents(1),
+                );
+        let key = Key::new(1);
+
+        repo.add(Account::open(1)).await.unwrap();
+
+        let mut first = repo.get(&key).await.unwrap();
+        let mut second = repo.get(&key).await.unwrap();
+
+        first.deposit(1);
+        second.deposit(2);
+        repo.update(first).await.unwrap();
+        repo.upsert(second).await.unwrap();
+
+        let snapshot = repo.snapshots().store().load(&key).await.unwrap();
+
+        assert_eq!(Some(3), snapshot.as_ref().map(|s| s.version));
+        assert_eq!(
+            3,
+            snapshot
+                .unwrap()
+                .restore::<Account>()
+                .unwrap()
+                .unwrap()
+                .balance
+        );
+        assert_eq!(3, repo.get(&key).await.unwrap().balance);
+    }
+
+    #[tokio::test]
+    async fn schema_version_mismatch_test() {
+        let repo =
+            EventSourcedRepo::<Account, _>::new(InMemoryEventStore::new())
+                .with_snapshots(
+                    InMemorySnapshotStore::new(),
+                    SnapshotPolicy::EveryEvents(1),
+                );
+        let key = Key::new(1);
+        let mut account = Account::open(1);
+
+        account.deposit(5);
+        repo.add(account).await.unwrap();
+
+        let mut stale = Snapshot::take(&Account::open(1), 2).unwrap();
+
+        stale.schema_version = Account::SNAPSHOT_VERSION + 1;
+        repo.snapshots().store().save(&key, stale).await.unwrap();
+
+        let mut account = repo.get(&key).await.unwrap();
+
+        assert_eq!(5, account.balance);
+
+        account.deposit(1);
+        repo.update(account).await.unwrap();
+
+        let snapshot = repo.snapshots().store().load(&key).await.unwrap();
+
+        assert_eq!(
+            Some((3, Account::SNAPSHOT_VERSION)),
+            snapshot.map(|s| (s.version, s.schema_version))
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupt_snapshot_test() {
+        let repo =
+            EventSourcedRepo::<Account, _>::new(InMemoryEventStore::new())
+                .with_snapshots(
+                    InMemorySnapshotStore::new(),
+                    SnapshotPolicy::EveryEvents(1),
+                );
+        let key = Key::new(1);
+
+        repo.add(Account::open(1)).await.unwrap();
+
+        let mut corrupt = Snapshot::take(&Account::open(1), 1).unwrap();
+
+        corrupt.state = serde_json::json!({ "balance": "none" });
+        repo.snapshots().store().save(&key, corrupt).await.unwrap();
+
+        assert!(matches!(repo.get(&key).await, Err(RepoError::Other(_))));
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use reddd_macros::MutableEntity;
+use serde::{Deserialize, Serialize};
+
+use super::{EventSourced, Snapshottable};
+use crate::domain::{Entity, Key, MutableEntity};
+
+/// A sample event-sourced entity to test event sourcing with.
+#[derive(Clone, Debug, MutableEntity, Serialize, Deserialize)]
+pub(crate) struct Account {
+    pub(crate) id: Key<Account, u32>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) updated_at: DateTime<Utc>,
+    pub(crate) balance: i64,
+    #[serde(skip)]
+    pub(crate) pending: Vec<AccountEvent>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum AccountEvent {
+    Opened { id: u32, at: DateTime<Utc> },
+    Deposited { amount: i64, at: DateTime<Utc> },
+}
+
+impl Account {
+    pub(crate) fn open(id: u32) -> Self {
+        let event = AccountEvent::Opened { id, at: Utc::now() };
+        let mut account = Self::from_first_event(&event);
+
+        account.pending.push(event);
+        account
+    }
+
+    pub(crate) fn deposit(&mut self, amount: i64) {
+        let event = AccountEvent::Deposited {
+            amount,
+            at: Utc::now(),
+        };
+
+        self.apply(&event);
+        self.pending.push(event);
+    }
+}
+
+impl EventSourced for Account {
+    type Event = AccountEvent;
+
+    fn from_first_event(event: &Self::Event) -> Self {
+        match *event {
+            | AccountEvent::Opened { id, at } => Self {
+                id: Key::new(id),
+                created_at: at,
+                updated_at: at,
+                balance: 0,
+                pending: Vec::new(),
+            },
+            | _ => panic!("accounts must be opened first"),
+        }
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match *event {
+            | AccountEvent::Opened { .. } => panic!("account is already open"),
+            | AccountEvent::Deposited { amount, at } => {
+                self.balance += amount;
+                self.updated_at = at;
+            }
+        }
+    }
+
+    fn take_pending_events(&mut self) -> Vec<Self::Event> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+impl Snapshottable for Account {
+    const SNAPSHOT_VERSION: u32 = 1;
+}
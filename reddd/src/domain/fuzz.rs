@@ -0,0 +1,262 @@
+use std::fmt::Debug;
+
+#[cfg(feature = "proptest")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "proptest")]
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    strategy::{Map, Strategy},
+};
+
+use super::TypedValue;
+#[cfg(feature = "proptest")]
+use super::{Entity, Key};
+
+/// The greatest timestamp (in seconds) generated by [`date_times`], which is
+/// the last second of the year 9999.
+#[cfg(feature = "proptest")]
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+#[cfg(feature = "arbitrary")]
+impl<'a, V, T> arbitrary::Arbitrary<'a> for TypedValue<V, T>
+where
+    V: Clone + Debug + PartialEq + PartialOrd + arbitrary::Arbitrary<'a>,
+{
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        V::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        V::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<V, T> Arbitrary for TypedValue<V, T>
+where
+    V: Clone + Debug + PartialEq + PartialOrd + Arbitrary,
+    T: Debug,
+{
+    type Parameters = V::Parameters;
+    type Strategy = Map<V::Strategy, fn(V) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        V::arbitrary_with(args).prop_map(Self::new as fn(V) -> Self)
+    }
+}
+
+/// Creates a strategy that generates instants between the Unix epoch and the
+/// end of the year 9999, to be used for the timestamps of entities.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, Entity, proptest_derive::Arbitrary)]
+/// struct User {
+///     #[proptest(strategy = "keys(uuids())")]
+///     id: Key<User, Uuid>,
+///     #[proptest(strategy = "date_times()")]
+///     created_at: DateTime<Utc>,
+///     name: String,
+/// }
+///
+/// proptest! {
+///     #[test]
+///     fn save_test(user in any::<User>()) {
+///         // --snip--
+///     }
+/// }
+/// ```
+#[cfg(feature = "proptest")]
+pub fn date_times() -> impl Strategy<Value = DateTime<Utc>> {
+    (0..=MAX_TIMESTAMP, 0..1_000_000_000u32).prop_map(|(secs, nanos)| {
+        DateTime::from_timestamp(secs, nanos).expect("timestamp in range")
+    })
+}
+
+/// Creates a strategy that generates random UUIDs, to be used for the keys
+/// of entities with [`keys`].
+#[cfg(feature = "proptest")]
+pub fn uuids() -> impl Strategy<Value = uuid::Uuid> {
+    any::<u128>().prop_map(uuid::Uuid::from_u128)
+}
+
+/// Creates a strategy that generates keys of entities of type `E`, which
+/// values are generated by `values`.
+///
+/// # Arguments
+///
+/// * `values` - The strategy to generate the values of keys with.
+#[cfg(feature = "proptest")]
+pub fn keys<E, V>(
+    values: impl Strategy<Value = V>,
+) -> impl Strategy<Value = Key<E, V>>
+where
+    E: Debug,
+    V: Clone + Debug + PartialEq + PartialOrd,
+{
+    values.prop_map(Key::<E, V>::new)
+}
+
+/// Creates a strategy that generates entities of type `E` with `build`, from
+/// keys generated by `keys`, and timestamps generated by [`date_times`],
+/// which are passed as the creation timestamp, then the one of the last
+/// update, which is never before the creation.
+///
+/// # Arguments
+///
+/// * `keys` - The strategy to generate the keys of entities with.
+/// * `build` - The function to build entities from their key, creation
+///   timestamp and last update timestamp with.
+///
+/// # Example
+///
+/// ```ignore
+/// proptest! {
+///     #[test]
+///     fn save_test(
+///         user in entities(keys(uuids()), |id, created_at, updated_at| {
+///             User { id, created_at, updated_at, name: "John".into() }
+///         }),
+///     ) {
+///         // --snip--
+///     }
+/// }
+/// ```
+#[cfg(feature = "proptest")]
+pub fn entities<K, E>(
+    keys: impl Strategy<Value = K>,
+    build: impl Fn(K, DateTime<Utc>, DateTime<Utc>) -> E,
+) -> impl Strategy<Value = E>
+where
+    K: Debug,
+    E: Entity<Key = K> + Debug,
+{
+    (keys, date_times(), date_times())
+        .prop_map(move |(key, t1, t2)| build(key, t1.min(t2), t1.max(t2)))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "proptest")]
+    use reddd_macros::MutableEntity;
+    use reddd_macros::ValueType;
+
+    use super::*;
+    #[cfg(feature = "proptest")]
+    use crate::domain::MutableEntity;
+    use crate::domain::{Key, ValueType};
+
+    #[derive(Debug)]
+    struct User;
+
+    fn is_even(value: &u32) -> Result<(), &'static str> {
+        match value % 2 {
+            | 0 => Ok(()),
+            | _ => Err("odd number"),
+        }
+    }
+
+    #[derive(Clone, Debug, ValueType)]
+    #[cfg_attr(feature = "proptest", value_type(proptest(min = 1, max = 100)))]
+    #[cfg_attr(
+        feature = "arbitrary",
+        value_type(arbitrary(min = 1, max = 100))
+    )]
+    struct Quantity(u32);
+
+    #[derive(Clone, Debug, ValueType)]
+    #[cfg_attr(feature = "proptest", value_type(proptest))]
+    #[cfg_attr(feature = "arbitrary", value_type(arbitrary))]
+    #[value_type(validate = is_even)]
+    struct Even(u32);
+
+    #[cfg(feature = "proptest")]
+    #[derive(Clone, Debug, ValueType)]
+    #[value_type(proptest(min_length = 1, max_length = 8))]
+    struct Name(String);
+
+    #[cfg(feature = "proptest")]
+    #[derive(Clone, Debug, ValueType)]
+    #[value_type(proptest(pattern = "[A-Z]{3}"))]
+    struct Code(String);
+
+    #[cfg(feature = "proptest")]
+    #[derive(Debug, MutableEntity)]
+    struct Account {
+        id: Key<Account, uuid::Uuid>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+
+    #[derive(Clone, Copy, Debug, ValueType)]
+    #[cfg_attr(feature = "proptest", value_type(proptest))]
+    #[cfg_attr(feature = "arbitrary", value_type(arbitrary))]
+    enum Status {
+        Active,
+        Closed,
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn proptest_value_type_test(
+            quantity in any::<Quantity>(),
+            even in any::<Even>(),
+            name in any::<Name>(),
+            code in any::<Code>(),
+            status in any::<Status>(),
+        ) {
+            proptest::prop_assert!((1..=100).contains(quantity.as_inner()));
+            proptest::prop_assert!(is_even(even.as_inner()).is_ok());
+            proptest::prop_assert!((1..=8).contains(&name.as_inner().chars().count()));
+            proptest::prop_assert!(code.as_inner().chars().all(|c| c.is_ascii_uppercase()));
+            proptest::prop_assert!(matches!(status, Status::Active | Status::Closed));
+        }
+
+        #[test]
+        fn proptest_entity_test(
+            user_id in any::<Key<User, u8>>(),
+            account in entities(keys(uuids()), |id, created_at, updated_at| {
+                Account { id, created_at, updated_at }
+            }),
+        ) {
+            proptest::prop_assert_eq!(user_id.clone(), Key::new(*user_id.as_inner()));
+            proptest::prop_assert_eq!(&account.id, account.id());
+            proptest::prop_assert!((0..=MAX_TIMESTAMP).contains(&account.created_at().timestamp()));
+            proptest::prop_assert!(account.created_at() <= account.updated_at());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    fn arbitrary_value_type_test() {
+        use arbitrary::Unstructured;
+
+        let bytes: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&bytes);
+
+        for _ in 0..64 {
+            let key =
+                <Key<User, u64> as arbitrary::Arbitrary>::arbitrary(&mut u)
+                    .unwrap();
+            let quantity =
+                <Quantity as arbitrary::Arbitrary>::arbitrary(&mut u).unwrap();
+            let status =
+                <Status as arbitrary::Arbitrary>::arbitrary(&mut u).unwrap();
+
+            assert_eq!(key, Key::new(*key.as_inner()));
+            assert!((1..=100).contains(quantity.as_inner()));
+            assert!(matches!(status, Status::Active | Status::Closed));
+
+            match <Even as arbitrary::Arbitrary>::arbitrary(&mut u) {
+                | Ok(even) => assert!(is_even(even.as_inner()).is_ok()),
+                | Err(e) => {
+                    assert!(matches!(e, arbitrary::Error::IncorrectFormat))
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,41 @@
+mod builder;
+mod diff;
 mod entity;
 mod repo;
+mod value_object;
 mod value_type;
 
+#[cfg(feature = "event-sourcing")]
+mod event_sourcing;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod fuzz;
+#[cfg(feature = "utoipa")]
+mod openapi;
+#[cfg(feature = "outbox")]
+mod outbox;
+#[cfg(feature = "secret")]
+mod secret;
 #[cfg(feature = "usecase")]
 mod usecase;
+#[cfg(feature = "values")]
+pub mod values;
 
+pub use builder::*;
+pub use diff::*;
 pub use entity::*;
 pub use repo::*;
+pub use value_object::*;
 pub use value_type::*;
 
+#[cfg(feature = "event-sourcing")]
+pub use event_sourcing::*;
+#[cfg(feature = "proptest")]
+pub use fuzz::*;
+#[cfg(feature = "utoipa")]
+pub use openapi::*;
+#[cfg(feature = "outbox")]
+pub use outbox::*;
+#[cfg(feature = "secret")]
+pub use secret::*;
 #[cfg(feature = "usecase")]
 pub use usecase::*;
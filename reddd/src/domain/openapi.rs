@@ -0,0 +1,319 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use utoipa::openapi::{
+    schema::{KnownFormat, ObjectBuilder, SchemaFormat, Type},
+    RefOr,
+    Schema,
+};
+pub use utoipa::{PartialSchema, ToSchema};
+
+use super::TypedValue;
+
+/// The OpenAPI schema of a type, as described by [`PartialSchema`].
+pub type OpenApiSchema = RefOr<Schema>;
+
+/// A trait to be implemented by the inner values of value types, so value
+/// types that wrap them (e.g. [`Key`](super::Key)) can be described in
+/// OpenAPI docs.
+///
+/// This trait is implemented for primitives, strings, [`uuid::Uuid`] and
+/// [`DateTime<Utc>`], and is derived along with [`ToSchema`] for value types
+/// with `#[value_type(openapi)]` (see [`ValueType`](super::ValueType)).
+///
+/// Keys should be named with type aliases (e.g. `type UserId = Key<User,
+/// Uuid>`) in types that derive [`ToSchema`] or `IntoParams`, as the derives
+/// otherwise require the entity type to implement [`ToSchema`] as well, and
+/// their entity types implement [`SchemaTag`] to name their schemas.
+///
+/// # Example
+///
+/// ```ignore
+/// impl ValueSchema for Ulid {
+///     fn value_schema() -> OpenApiSchema {
+///         ObjectBuilder::new()
+///             .schema_type(Type::String)
+///             .format(Some(SchemaFormat::KnownFormat(KnownFormat::Ulid)))
+///             .into()
+///     }
+/// }
+/// ```
+pub trait ValueSchema {
+    /// Gets the OpenAPI schema of the value.
+    fn value_schema() -> OpenApiSchema;
+}
+
+macro_rules! impl_value_schema {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ValueSchema for $ty {
+                fn value_schema() -> OpenApiSchema {
+                    <$ty as PartialSchema>::schema()
+                }
+            }
+        )*
+    };
+}
+
+impl_value_schema!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64,
+    bool, char, str, String,
+);
+
+impl<V: ValueSchema + ?Sized> ValueSchema for &V {
+    fn value_schema() -> OpenApiSchema {
+        V::value_schema()
+    }
+}
+
+impl ValueSchema for uuid::Uuid {
+    fn value_schema() -> OpenApiSchema {
+        string_schema(KnownFormat::Uuid)
+    }
+}
+
+impl ValueSchema for DateTime<Utc> {
+    fn value_schema() -> OpenApiSchema {
+        string_schema(KnownFormat::DateTime)
+    }
+}
+
+/// Creates the schema of strings of the given `format`.
+fn string_schema(format: KnownFormat) -> OpenApiSchema {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .format(Some(SchemaFormat::KnownFormat(format)))
+        .into()
+}
+
+impl<V, T> PartialSchema for TypedValue<V, T>
+where
+    V: Clone + std::fmt::Debug + PartialEq + PartialOrd + ValueSchema,
+{
+    fn schema() -> OpenApiSchema {
+        V::value_schema()
+    }
+}
+
+impl<V, T> ToSchema for TypedValue<V, T>
+where
+    V: Clone + std::fmt::Debug + PartialEq + PartialOrd + ValueSchema,
+    T: SchemaTag,
+{
+    fn name() -> Cow<'static, str> {
+        Cow::Owned(format!("TypedValue_{}", T::schema_tag()))
+    }
+}
+
+/// A trait to be implemented by the types that typed values are tied to
+/// (e.g. the entities of [`Key`](super::Key)s), which names the OpenAPI
+/// schemas of the typed values, as `TypedValue_{tag}`.
+///
+/// This trait is implemented for types that implement [`ToSchema`], which
+/// are tagged with their schema name, and is to be implemented otherwise.
+/// Tags must be unique among the typed values of an OpenAPI doc.
+///
+/// # Example
+///
+/// ```ignore
+/// impl SchemaTag for User {
+///     fn schema_tag() -> Cow<'static, str> {
+///         Cow::Borrowed("User")
+///     }
+/// }
+///
+/// // named `TypedValue_User`
+/// type UserId = Key<User, Uuid>;
+/// ```
+pub trait SchemaTag {
+    /// Gets the tag of the type in the schema names of its typed values.
+    fn schema_tag() -> Cow<'static, str>;
+}
+
+impl<T: ToSchema> SchemaTag for T {
+    fn schema_tag() -> Cow<'static, str> {
+        T::name()
+    }
+}
+
+/// A struct that holds the constraints declared on a value type with
+/// `#[value_type(openapi(..))]`, which narrow down the schema of its inner
+/// value.
+#[derive(Clone, Default)]
+pub struct SchemaConstraints {
+    /// The inclusive minimum of numbers.
+    pub minimum: Option<utoipa::Number>,
+
+    /// The inclusive maximum of numbers.
+    pub maximum: Option<utoipa::Number>,
+
+    /// The minimum length of strings, or number of items of arrays.
+    pub min_length: Option<usize>,
+
+    /// The maximum length of strings, or number of items of arrays.
+    pub max_length: Option<usize>,
+
+    /// The regular expression that strings must match.
+    pub pattern: Option<&'static str>,
+
+    /// The values that are allowed, if restricted.
+    pub enum_values: Option<Vec<serde_json::Value>>,
+}
+
+impl SchemaConstraints {
+    /// Narrows `schema` down with the constraints, leaving references and
+    /// composite schemas as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to narrow down.
+    pub fn apply(self, schema: OpenApiSchema) -> OpenApiSchema {
+        match schema {
+            | RefOr::T(Schema::Object(mut object)) => {
+                object.minimum = self.minimum.or(object.minimum);
+                object.maximum = self.maximum.or(object.maximum);
+                object.min_length = self.min_length.or(object.min_length);
+                object.max_length = self.max_length.or(object.max_length);
+                object.pattern =
+                    self.pattern.map(String::from).or(object.pattern);
+                object.enum_values = self.enum_values.or(object.enum_values);
+
+                RefOr::T(Schema::Object(object))
+            }
+            | RefOr::T(Schema::Array(mut array)) => {
+                array.min_items = self.min_length.or(array.min_items);
+                array.max_items = self.max_length.or(array.max_items);
+
+                RefOr::T(Schema::Array(array))
+            }
+            | schema => schema,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reddd_macros::ValueType;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::{Key, ValueType};
+
+    struct User;
+
+    impl SchemaTag for User {
+        fn schema_tag() -> Cow<'static, str> {
+            Cow::Borrowed("User")
+        }
+    }
+
+    type UserId = Key<User, Uuid>;
+
+    const USER_ID: &str = "TypedValue_User";
+
+    #[derive(Clone, ValueType)]
+    #[value_type(openapi(min = 0, max = 100))]
+    struct Percentage(u8);
+
+    #[derive(Clone, ValueType)]
+    #[value_type(openapi(min_length = 1, pattern = "^[a-z]+$"))]
+    struct Tag(String);
+
+    #[derive(Clone, Copy, ValueType)]
+    #[value_type(openapi)]
+    enum Status {
+        Active,
+        #[value_type(name = "closed")]
+        Closed,
+    }
+
+    #[derive(ToSchema)]
+    #[allow(dead_code)]
+    struct Body {
+        id: UserId,
+        progress: Percentage,
+        tags: Vec<Tag>,
+        status: Status,
+    }
+
+    #[derive(utoipa::IntoParams)]
+    #[allow(dead_code)]
+    struct UserPath {
+        id: UserId,
+    }
+
+    fn to_value<S: PartialSchema>() -> serde_json::Value {
+        serde_json::to_value(S::schema()).unwrap()
+    }
+
+    #[test]
+    fn typed_value_test() {
+        assert_eq!(
+            serde_json::json!({ "type": "string", "format": "uuid" }),
+            to_value::<UserId>()
+        );
+        assert_eq!(USER_ID, UserId::name());
+        assert_eq!(to_value::<i64>(), to_value::<TypedValue<i64, User>>());
+    }
+
+    #[test]
+    fn schema_tag_test() {
+        #[derive(ToSchema)]
+        #[schema(as = billing::Account)]
+        #[allow(dead_code)]
+        struct Account {
+            balance: i64,
+        }
+
+        assert_eq!("TypedValue_billing.Account", Key::<Account, u32>::name());
+    }
+
+    #[test]
+    fn value_type_test() {
+        let percentage = to_value::<Percentage>();
+        let tag = to_value::<Tag>();
+
+        assert_eq!("integer", percentage["type"]);
+        assert_eq!(0, percentage["minimum"]);
+        assert_eq!(100, percentage["maximum"]);
+        assert_eq!("string", tag["type"]);
+        assert_eq!(1, tag["minLength"]);
+        assert_eq!("^[a-z]+$", tag["pattern"]);
+        assert_eq!(
+            serde_json::json!({ "type": "string", "enum": ["Active", "closed"] }),
+            to_value::<Status>()
+        );
+        assert_eq!("Percentage", Percentage::name());
+    }
+
+    #[test]
+    fn derive_test() {
+        let body = to_value::<Body>();
+        let mut schemas = Vec::new();
+
+        Body::schemas(&mut schemas);
+
+        assert_eq!(
+            format!("#/components/schemas/{USER_ID}"),
+            body["properties"]["id"]["$ref"]
+        );
+        assert_eq!(
+            "#/components/schemas/Tag",
+            body["properties"]["tags"]["items"]["$ref"]
+        );
+        assert_eq!(
+            vec![USER_ID, "Percentage", "Tag", "Status"],
+            schemas.iter().map(|(name, _)| name).collect::<Vec<_>>()
+        );
+
+        let params = serde_json::to_value(
+            <UserPath as utoipa::IntoParams>::into_params(|| None),
+        )
+        .unwrap();
+
+        assert_eq!(
+            format!("#/components/schemas/{USER_ID}"),
+            params[0]["schema"]["$ref"]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    sync::{Mutex, MutexGuard},
+};
+
+use chrono::Utc;
+
+use super::{EmitsEvents, Outbox, OutboxMessage};
+use crate::domain::{
+    error::{RepoError, RepoResult},
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+
+/// The state of an [`InMemoryOutboxRepo`], which is changed as a whole.
+struct State<E: EmitsEvents> {
+    items: HashMap<E::Key, E>,
+    outbox: VecDeque<OutboxMessage<E::Event>>,
+    dead_letters: Vec<OutboxMessage<E::Event>>,
+    next_id: u64,
+}
+
+impl<E: EmitsEvents> State<E> {
+    /// Moves the events raised by `item` to the outbox.
+    fn enqueue(&mut self, item: &mut E) {
+        let now = Utc::now();
+
+        for event in item.take_events() {
+            self.next_id += 1;
+            self.outbox.push_back(OutboxMessage {
+                id: self.next_id,
+                event,
+                created_at: now,
+                attempts: 0,
+            });
+        }
+    }
+}
+
+/// A repository that keeps entities along with an [`Outbox`] of their events
+/// in memory.
+///
+/// Entities and their events are written under a single lock, thus events
+/// are only kept for successful writes.
+///
+/// This repository is mostly useful for tests and local development, as
+/// entities and events are lost once the repository is dropped.
+pub struct InMemoryOutboxRepo<E: EmitsEvents> {
+    state: Mutex<State<E>>,
+}
+
+impl<E: EmitsEvents> InMemoryOutboxRepo<E> {
+    /// Creates a new empty repository.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State {
+                items: HashMap::new(),
+                outbox: VecDeque::new(),
+                dead_letters: Vec::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<E>> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl<E: EmitsEvents> Default for InMemoryOutboxRepo<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn not_found() -> RepoError {
+    RepoError::NotFound("item does not exist".into())
+}
+
+#[async_trait::async_trait]
+impl<E> ReadRepo for InMemoryOutboxRepo<E>
+where
+    E: EmitsEvents + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + PartialOrd + Send + Sync,
+{
+    type Entity = E;
+
+    async fn get(&self, key: &E::Key) -> RepoResult<E> {
+        self.state().items.get(key).cloned().ok_or_else(not_found)
+    }
+
+    async fn get_page(&self, params: Pagination<E>) -> RepoResult<Vec<E>> {
+        let mut items: Vec<_> = self
+            .state()
+            .items
+            .values()
+            .filter(|i| {
+                (i.created_at(), i.id())
+                    < (&params.before_timestamp, &params.before_key)
+            })
+            .cloned()
+            .collect();
+
+        items.sort_by(|a, b| {
+            (b.created_at(), b.id())
+                .partial_cmp(&(a.created_at(), a.id()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        items.truncate(params.page_size);
+
+        Ok(items)
+    }
+
+    async fn exists(&self, key: &E::Key) -> RepoResult<bool> {
+        Ok(self.state().items.contains_key(key))
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> WriteRepo for InMemoryOutboxRepo<E>
+where
+    E: EmitsEvents + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + Send + Sync,
+    E::Event: Send,
+{
+    type Entity = E;
+
+    async fn add(&self, mut item: E) -> RepoResult<E> {
+        let mut state = self.state();
+
+        if state.items.contains_key(item.id()) {
+            return Err(RepoError::DuplicateValue("item exists".into()));
+        }
+
+        state.enqueue(&mut item);
+        state.items.insert(item.id().clone(), item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(&self, mut item: E) -> RepoResult<E> {
+        let mut state = self.state();
+
+        if !state.items.contains_key(item.id()) {
+            return Err(not_found());
+        }
+
+        state.enqueue(&mut item);
+        state.items.insert(item.id().clone(), item.clone());
+
+        Ok(item)
+    }
+
+    async fn upsert(&self, mut item: E) -> RepoResult<Upserted<E>> {
+        let mut state = self.state();
+
+        state.enqueue(&mut item);
+
+        match state.items.insert(item.id().clone(), item.clone()) {
+            | Some(_) => Ok(Upserted::Updated(item)),
+            | None => Ok(Upserted::Inserted(item)),
+        }
+    }
+
+    async fn update_if(
+        &self,
+        mut item: E,
+        predicate: &Predicate<'_, E>,
+    ) -> RepoResult<E> {
+        let mut state = self.state();
+        let stored = state.items.get(item.id()).ok_or_else(not_found)?;
+
+        if !predicate(stored) {
+            return Err(RepoError::PreconditionFailed(
+                "stored item does not satisfy the predicate".into(),
+            ));
+        }
+
+        state.enqueue(&mut item);
+        state.items.insert(item.id().clone(), item.clone());
+
+        Ok(item)
+    }
+
+    async fn remove(&self, key: &E::Key) -> RepoResult<()> {
+        self.state()
+            .items
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(not_found)
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> Outbox for InMemoryOutboxRepo<E>
+where
+    E: EmitsEvents + Send,
+    E::Key: Send,
+{
+    type Event = E::Event;
+
+    async fn pending(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<E::Event>>> {
+        Ok(self.state().outbox.iter().take(limit).cloned().collect())
+    }
+
+    async fn mark_published(&self, ids: &[u64]) -> RepoResult<()> {
+        self.state().outbox.retain(|m| !ids.contains(&m.id));
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: u64) -> RepoResult<()> {
+        self.state()
+            .outbox
+            .iter_mut()
+            .filter(|m| m.id == id)
+            .for_each(|m| m.attempts += 1);
+
+        Ok(())
+    }
+
+    async fn mark_dead(&self, id: u64) -> RepoResult<()> {
+        let mut state = self.state();
+
+        if let Some(index) = state.outbox.iter().position(|m| m.id == id) {
+            let mut message = state.outbox.remove(index).unwrap();
+
+            message.attempts += 1;
+            state.dead_letters.push(message);
+        }
+
+        Ok(())
+    }
+
+    async fn dead_letters(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<E::Event>>> {
+        Ok(self
+            .state()
+            .dead_letters
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        outbox::testing::{Order, OrderEvent},
+        Key,
+    };
+
+    #[tokio::test]
+    async fn unit_of_work_test() {
+        let repo = InMemoryOutboxRepo::new();
+        let mut order = repo.add(Order::place(1)).await.unwrap();
+
+        assert!(order.events.is_empty());
+
+        order.ship();
+        repo.update(order).await.unwrap();
+
+        assert!(repo.add(Order::place(1)).await.is_err());
+        assert!(repo.update(Order::place(2)).await.is_err());
+        assert!(repo.update_if(Order::place(1), &|_| false).await.is_err());
+
+        let pending = repo.pending(10).await.unwrap();
+        let events: Vec<_> = pending.iter().map(|m| m.event.clone()).collect();
+
+        assert_eq!(vec![OrderEvent::Placed(1), OrderEvent::Shipped(1)], events);
+        assert_eq!("shipped", repo.get(&Key::new(1)).await.unwrap().status);
+    }
+
+    #[tokio::test]
+    async fn mark_test() {
+        let repo = InMemoryOutboxRepo::new();
+
+        repo.add(Order::place(1)).await.unwrap();
+        repo.add(Order::place(2)).await.unwrap();
+
+        let ids: Vec<_> = repo
+            .pending(10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+
+        repo.mark_failed(ids[1]).await.unwrap();
+        repo.mark_published(&ids[..1]).await.unwrap();
+
+        let pending = repo.pending(10).await.unwrap();
+
+        assert_eq!(1, pending.len());
+        assert_eq!(OrderEvent::Placed(2), pending[0].event);
+        assert_eq!(1, pending[0].attempts);
+
+        repo.mark_dead(ids[1]).await.unwrap();
+
+        let dead_letters = repo.dead_letters(10).await.unwrap();
+
+        assert!(repo.pending(10).await.unwrap().is_empty());
+        assert_eq!(1, dead_letters.len());
+        assert_eq!(OrderEvent::Placed(2), dead_letters[0].event);
+        assert_eq!(2, dead_letters[0].attempts);
+    }
+}
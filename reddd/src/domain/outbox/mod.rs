@@ -0,0 +1,177 @@
+use std::{error::Error, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{error::RepoResult, MutableEntity};
+
+mod memory;
+mod relay;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub use memory::*;
+pub use relay::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
+/// A trait to be implemented by entities which raise domain events as their
+/// state changes, to be published once the changes are persisted.
+///
+/// Repositories that support an [`Outbox`] take the raised events out of
+/// written entities, and persist them along with the entities in a single
+/// unit of work, so events are never lost nor published for changes that
+/// were not persisted.
+///
+/// # Example
+///
+/// ```ignore
+/// impl Order {
+///     pub fn ship(&mut self) {
+///         self.status = OrderStatus::Shipped;
+///         self.events.push(OrderEvent::Shipped { id: self.id.clone() });
+///     }
+/// }
+///
+/// impl EmitsEvents for Order {
+///     type Event = OrderEvent;
+///
+///     fn take_events(&mut self) -> Vec<Self::Event> {
+///         std::mem::take(&mut self.events)
+///     }
+/// }
+/// ```
+pub trait EmitsEvents: MutableEntity {
+    /// The type of the events raised by the entity.
+    type Event: Clone + Send + Sync;
+
+    /// Moves the events that were raised by the entity, but were not
+    /// persisted yet, out of `self`.
+    fn take_events(&mut self) -> Vec<Self::Event>;
+}
+
+/// A struct that holds an event persisted in an outbox, waiting to be
+/// published.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutboxMessage<Ev> {
+    /// The identifier of the message, which increases in the order events
+    /// were persisted in.
+    pub id: u64,
+
+    /// The persisted event.
+    pub event: Ev,
+
+    /// The time the event was persisted at.
+    pub created_at: DateTime<Utc>,
+
+    /// The number of failed attempts to publish the event so far.
+    pub attempts: u32,
+}
+
+/// A trait to be implemented by outboxes, which keep persisted events until
+/// they are published.
+#[async_trait::async_trait]
+pub trait Outbox: Sync {
+    /// The type of the events kept in the outbox.
+    type Event: Send + Sync;
+
+    /// Gets the oldest messages that were not published yet, in the order
+    /// they were persisted in.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of messages to get.
+    async fn pending(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<Self::Event>>>;
+
+    /// Marks messages as published, so they are not returned as pending
+    /// anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The identifiers of the published messages.
+    async fn mark_published(&self, ids: &[u64]) -> RepoResult<()>;
+
+    /// Records a failed attempt to publish a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the message.
+    async fn mark_failed(&self, id: u64) -> RepoResult<()>;
+
+    /// Records a final failed attempt to publish a message, and moves it to
+    /// the dead letters, so it is not returned as pending anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the message.
+    async fn mark_dead(&self, id: u64) -> RepoResult<()>;
+
+    /// Gets the oldest messages that were given up on, in the order they
+    /// were persisted in.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of messages to get.
+    async fn dead_letters(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<Self::Event>>>;
+}
+
+#[async_trait::async_trait]
+impl<O> Outbox for Arc<O>
+where
+    O: Outbox + Send + ?Sized,
+{
+    type Event = O::Event;
+
+    async fn pending(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<Self::Event>>> {
+        (**self).pending(limit).await
+    }
+
+    async fn mark_published(&self, ids: &[u64]) -> RepoResult<()> {
+        (**self).mark_published(ids).await
+    }
+
+    async fn mark_failed(&self, id: u64) -> RepoResult<()> {
+        (**self).mark_failed(id).await
+    }
+
+    async fn mark_dead(&self, id: u64) -> RepoResult<()> {
+        (**self).mark_dead(id).await
+    }
+
+    async fn dead_letters(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<Self::Event>>> {
+        (**self).dead_letters(limit).await
+    }
+}
+
+/// A type alias for errors returned by event buses.
+pub type PublishError = Box<dyn Error + Send + Sync>;
+
+/// A trait to be implemented by event buses, which deliver events to their
+/// subscribers (e.g. message brokers).
+#[async_trait::async_trait]
+pub trait EventBus: Sync {
+    /// The type of the published events.
+    type Event: Send + Sync;
+
+    /// Publishes an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish.
+    async fn publish(&self, event: &Self::Event) -> Result<(), PublishError>;
+}
@@ -0,0 +1,287 @@
+use std::{convert::Infallible, time::Duration};
+
+use super::{EventBus, Outbox};
+use crate::domain::error::RepoResult;
+
+/// A struct that holds configuration of an [`OutboxRelay`].
+#[derive(Clone, Copy, Debug)]
+pub struct RelayConfig {
+    /// The maximum number of messages to relay at once.
+    pub batch_size: usize,
+
+    /// The time to wait before polling the outbox again, once it is drained.
+    pub poll_interval: Duration,
+
+    /// The number of attempts after which a message that fails to be
+    /// published is moved to the dead letters, so it does not block the
+    /// messages after it forever, or [`None`] to retry messages endlessly.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+            max_attempts: Some(10),
+        }
+    }
+}
+
+/// A relay that polls an [`Outbox`] and forwards its messages to an
+/// [`EventBus`], in the order they were persisted in.
+///
+/// Messages are marked as published only after the bus accepts them, thus
+/// delivery is *at-least-once*: a message may be published more than once if
+/// the relay stops between publishing it and marking it, and subscribers
+/// should handle duplicates. Messages that keep failing are moved to the
+/// [dead letters](Outbox::dead_letters) after
+/// [`max_attempts`](RelayConfig::max_attempts), which lets the messages after
+/// them through.
+///
+/// # Example
+///
+/// ```ignore
+/// let orders_repo = Arc::new(InMemoryOutboxRepo::<Order>::new());
+/// let relay = OutboxRelay::new(
+///     orders_repo.clone(),
+///     my_bus,
+///     RelayConfig::default(),
+/// );
+///
+/// tokio::spawn(async move { relay.run().await });
+/// ```
+pub struct OutboxRelay<O, B> {
+    outbox: O,
+    bus: B,
+    config: RelayConfig,
+}
+
+impl<O, B> OutboxRelay<O, B>
+where
+    O: Outbox,
+    B: EventBus<Event = O::Event>,
+{
+    /// Creates a new relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `outbox` - The outbox to poll messages from.
+    /// * `bus` - The bus to publish messages to.
+    /// * `config` - The relay configuration.
+    pub fn new(outbox: O, bus: B, config: RelayConfig) -> Self {
+        Self {
+            outbox,
+            bus,
+            config,
+        }
+    }
+
+    /// Gets a reference to the polled outbox.
+    pub fn outbox(&self) -> &O {
+        &self.outbox
+    }
+
+    /// Gets a reference to the bus messages are published to.
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    /// Relays a single batch of pending messages, and returns the number of
+    /// published ones.
+    ///
+    /// Relaying stops at the first message the bus fails to publish, which
+    /// is kept pending along with the ones after it to preserve ordering,
+    /// unless it reached [`max_attempts`](RelayConfig::max_attempts).
+    pub async fn relay_once(&self) -> RepoResult<usize> {
+        let messages = self.outbox.pending(self.config.batch_size).await?;
+        let mut published = Vec::with_capacity(messages.len());
+        let mut failed = None;
+
+        for message in messages {
+            if self.bus.publish(&message.event).await.is_err() {
+                failed = Some(message);
+                break;
+            }
+
+            published.push(message.id);
+        }
+
+        // published messages are marked first, so they are not published
+        // again if marking the failed one fails
+        if !published.is_empty() {
+            self.outbox.mark_published(&published).await?;
+        }
+
+        if let Some(message) = failed {
+            match self.config.max_attempts {
+                | Some(max) if message.attempts + 1 >= max => {
+                    self.outbox.mark_dead(message.id).await?
+                }
+                | _ => self.outbox.mark_failed(message.id).await?,
+            }
+        }
+
+        Ok(published.len())
+    }
+
+    /// Relays messages continuously, waiting for
+    /// [`poll_interval`](RelayConfig::poll_interval) whenever a batch is not
+    /// full, until the outbox fails.
+    pub async fn run(&self) -> RepoResult<Infallible> {
+        loop {
+            if self.relay_once().await? < self.config.batch_size {
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::domain::{
+        error::RepoError,
+        outbox::{
+            testing::{Order, OrderEvent, RecordingBus},
+            InMemoryOutboxRepo,
+            OutboxMessage,
+        },
+        WriteRepo,
+    };
+
+    fn relay(
+        batch_size: usize,
+    ) -> OutboxRelay<Arc<InMemoryOutboxRepo<Order>>, RecordingBus<OrderEvent>>
+    {
+        OutboxRelay::new(
+            Arc::new(InMemoryOutboxRepo::new()),
+            RecordingBus::new(),
+            RelayConfig {
+                batch_size,
+                poll_interval: Duration::from_millis(1),
+                max_attempts: Some(2),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn relay_once_test() {
+        let relay = relay(2);
+
+        for id in 1..=3 {
+            relay.outbox().add(Order::place(id)).await.unwrap();
+        }
+
+        assert_eq!(2, relay.relay_once().await.unwrap());
+        assert_eq!(1, relay.relay_once().await.unwrap());
+        assert_eq!(0, relay.relay_once().await.unwrap());
+
+        assert_eq!(
+            vec![
+                OrderEvent::Placed(1),
+                OrderEvent::Placed(2),
+                OrderEvent::Placed(3)
+            ],
+            relay.bus().published()
+        );
+    }
+
+    #[tokio::test]
+    async fn bus_failure_test() {
+        let relay = relay(10);
+
+        relay.outbox().add(Order::place(1)).await.unwrap();
+        relay.outbox().add(Order::place(2)).await.unwrap();
+        relay.bus().fail_next(1);
+
+        assert_eq!(0, relay.relay_once().await.unwrap());
+
+        let pending = relay.outbox().pending(10).await.unwrap();
+
+        assert_eq!(2, pending.len());
+        assert_eq!(1, pending[0].attempts);
+
+        assert_eq!(2, relay.relay_once().await.unwrap());
+        assert_eq!(
+            vec![OrderEvent::Placed(1), OrderEvent::Placed(2)],
+            relay.bus().published()
+        );
+    }
+
+    /// An outbox that fails to record failed attempts.
+    struct FailingMarks(InMemoryOutboxRepo<Order>);
+
+    #[async_trait::async_trait]
+    impl Outbox for FailingMarks {
+        type Event = OrderEvent;
+
+        async fn pending(
+            &self,
+            limit: usize,
+        ) -> RepoResult<Vec<OutboxMessage<OrderEvent>>> {
+            self.0.pending(limit).await
+        }
+
+        async fn mark_published(&self, ids: &[u64]) -> RepoResult<()> {
+            self.0.mark_published(ids).await
+        }
+
+        async fn mark_failed(&self, _: u64) -> RepoResult<()> {
+            Err(RepoError::Other("outbox is unavailable".into()))
+        }
+
+        async fn mark_dead(&self, _: u64) -> RepoResult<()> {
+            Err(RepoError::Other("outbox is unavailable".into()))
+        }
+
+        async fn dead_letters(
+            &self,
+            limit: usize,
+        ) -> RepoResult<Vec<OutboxMessage<OrderEvent>>> {
+            self.0.dead_letters(limit).await
+        }
+    }
+
+    #[tokio::test]
+    async fn dead_letter_test() {
+        let relay = relay(10);
+
+        relay.outbox().add(Order::place(1)).await.unwrap();
+        relay.outbox().add(Order::place(2)).await.unwrap();
+        relay.bus().fail_next(2);
+
+        assert_eq!(0, relay.relay_once().await.unwrap());
+        assert_eq!(0, relay.relay_once().await.unwrap());
+        assert_eq!(1, relay.relay_once().await.unwrap());
+
+        let dead_letters = relay.outbox().dead_letters(10).await.unwrap();
+
+        assert_eq!(vec![OrderEvent::Placed(2)], relay.bus().published());
+        assert_eq!(1, dead_letters.len());
+        assert_eq!(OrderEvent::Placed(1), dead_letters[0].event);
+        assert_eq!(2, dead_letters[0].attempts);
+    }
+
+    #[tokio::test]
+    async fn mark_failed_error_test() {
+        let relay = OutboxRelay::new(
+            FailingMarks(InMemoryOutboxRepo::new()),
+            RecordingBus::new(),
+            RelayConfig::default(),
+        );
+
+        relay.outbox().0.add(Order::place(1)).await.unwrap();
+        relay.outbox().0.add(Order::place(2)).await.unwrap();
+        relay.bus().fail_on(OrderEvent::Placed(2));
+
+        assert!(relay.relay_once().await.is_err());
+
+        let pending = relay.outbox().pending(10).await.unwrap();
+
+        assert_eq!(1, pending.len());
+        assert_eq!(OrderEvent::Placed(2), pending[0].event);
+    }
+}
@@ -0,0 +1,737 @@
+use std::{
+    marker::PhantomData,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{
+    ffi,
+    params,
+    types::Value,
+    Connection,
+    OptionalExtension,
+    Transaction,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{EmitsEvents, Outbox, OutboxMessage};
+use crate::domain::{
+    error::{RepoError, RepoResult},
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entities (
+        key TEXT PRIMARY KEY,
+        sort_key NOT NULL,
+        created_at TEXT NOT NULL,
+        state TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS entities_created_at
+        ON entities (created_at, sort_key);
+
+    CREATE TABLE IF NOT EXISTS outbox (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        event TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        published_at TEXT,
+        dead_at TEXT
+    );
+";
+
+/// A repository that persists entities along with an [`Outbox`] of their
+/// events to an SQLite database.
+///
+/// Entities and events are stored as JSON, and are written in a single
+/// transaction. Each repository expects a database of its own.
+///
+/// Queries run on blocking tasks of the Tokio runtime, one at a time, so they
+/// do not block other tasks while waiting for the database.
+///
+/// Pages are ordered by keys as stored in SQLite, so keys that serialize to
+/// numbers, strings or booleans are ordered as expected, while keys that
+/// serialize to sequences or maps are ordered by their JSON text.
+pub struct SqliteOutboxRepo<E> {
+    conn: Arc<Mutex<Connection>>,
+    _entity: PhantomData<fn() -> E>,
+}
+
+impl<E> SqliteOutboxRepo<E> {
+    /// Opens the repository at `path`, creating the database if it does not
+    /// exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database file.
+    pub fn open(path: impl AsRef<Path>) -> RepoResult<Self> {
+        Self::with_connection(Connection::open(path).map_err(sql_error)?)
+    }
+
+    /// Opens a repository on a new in-memory database.
+    pub fn open_in_memory() -> RepoResult<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(sql_error)?)
+    }
+
+    /// Creates a repository on top of an open connection, creating the
+    /// needed tables if they do not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - The connection to the database.
+    pub fn with_connection(conn: Connection) -> RepoResult<Self> {
+        conn.execute_batch(SCHEMA).map_err(sql_error)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            _entity: PhantomData,
+        })
+    }
+
+    /// Runs `f` on the connection in a blocking task, which runs to
+    /// completion even if the returned future is dropped.
+    async fn run<T, F>(&self, f: F) -> RepoResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> RepoResult<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+
+        tokio::task::spawn_blocking(move || f(&mut conn.lock().unwrap()))
+            .await
+            .map_err(|err| RepoError::Other(err.into()))?
+    }
+}
+
+/// A struct that holds an entity as stored in the database.
+#[derive(Clone)]
+struct Row {
+    key: String,
+    sort_key: Value,
+    created_at: String,
+    state: String,
+}
+
+impl<E> SqliteOutboxRepo<E>
+where
+    E: EmitsEvents + Serialize,
+    E::Key: Serialize,
+    E::Event: Serialize,
+{
+    /// Converts `item` to a row, along with the events it raised, which are
+    /// moved out of `item`.
+    fn encode(item: &mut E) -> RepoResult<(Row, Vec<String>)> {
+        let row = Row {
+            key: to_json(item.id())?,
+            sort_key: sort_key(item.id())?,
+            created_at: timestamp(item.created_at()),
+            state: to_json(item)?,
+        };
+        let events = item
+            .take_events()
+            .iter()
+            .map(to_json)
+            .collect::<RepoResult<_>>()?;
+
+        Ok((row, events))
+    }
+
+    /// Runs `op` on `row` and the stored state of its entity in a
+    /// transaction, then adds `events` to the outbox and commits.
+    async fn write<T>(
+        &self,
+        row: Row,
+        events: Vec<String>,
+        op: impl FnOnce(&Transaction, &Row, Option<String>) -> RepoResult<T>
+            + Send
+            + 'static,
+    ) -> RepoResult<T>
+    where
+        T: Send + 'static,
+    {
+        self.run(move |conn| {
+            let tx = conn.transaction().map_err(sql_error)?;
+            let stored = state(&tx, &row.key)?;
+            let result = op(&tx, &row, stored)?;
+            let now = timestamp(&Utc::now());
+
+            for event in events {
+                tx.execute(
+                    "INSERT INTO outbox (event, created_at) VALUES (?1, ?2)",
+                    params![event, now],
+                )
+                .map_err(sql_error)?;
+            }
+
+            tx.commit().map_err(sql_error)?;
+
+            Ok(result)
+        })
+        .await
+    }
+}
+
+/// Gets the stored state of the entity with the passed JSON key, if any.
+fn state(conn: &Connection, key: &str) -> RepoResult<Option<String>> {
+    conn.query_row("SELECT state FROM entities WHERE key = ?1", [key], |row| {
+        row.get::<_, String>(0)
+    })
+    .optional()
+    .map_err(sql_error)
+}
+
+/// Saves `row`, replacing the stored one with the same key.
+fn save(tx: &Transaction, row: &Row) -> RepoResult<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO entities (key, sort_key, created_at, state)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![row.key, row.sort_key, row.created_at, row.state],
+    )
+    .map_err(sql_error)?;
+
+    Ok(())
+}
+
+/// Gets the oldest outbox messages matching the `filter` condition.
+fn messages<Ev: DeserializeOwned>(
+    conn: &Connection,
+    filter: &str,
+    limit: usize,
+) -> RepoResult<Vec<OutboxMessage<Ev>>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, event, created_at, attempts FROM outbox
+             WHERE {filter} ORDER BY id LIMIT ?1"
+        ))
+        .map_err(sql_error)?;
+    let rows = stmt
+        .query_map([limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })
+        .map_err(sql_error)?;
+
+    rows.map(|row| {
+        let (id, event, created_at, attempts) = row.map_err(sql_error)?;
+
+        Ok(OutboxMessage {
+            id: id as u64,
+            event: from_json(&event)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|err| RepoError::Other(err.into()))?
+                .with_timezone(&Utc),
+            attempts,
+        })
+    })
+    .collect()
+}
+
+fn sql_error(err: rusqlite::Error) -> RepoError {
+    match err.sqlite_error().map(|e| e.extended_code) {
+        | Some(
+            ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY,
+        ) => RepoError::DuplicateValue(err.to_string()),
+        | _ => RepoError::Other(err.into()),
+    }
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> RepoResult<String> {
+    serde_json::to_string(value).map_err(|err| RepoError::Other(err.into()))
+}
+
+fn from_json<T: DeserializeOwned>(value: &str) -> RepoResult<T> {
+    serde_json::from_str(value).map_err(|err| RepoError::Other(err.into()))
+}
+
+/// Converts a key to a value SQLite can order, falling back to its JSON text
+/// for keys that are not scalars.
+fn sort_key<K: Serialize + ?Sized>(key: &K) -> RepoResult<Value> {
+    let value = serde_json::to_value(key)
+        .map_err(|err| RepoError::Other(err.into()))?;
+
+    Ok(match value {
+        | serde_json::Value::Bool(b) => Value::Integer(b.into()),
+        | serde_json::Value::Number(n) => match n.as_i64() {
+            | Some(n) => Value::Integer(n),
+            | None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        | serde_json::Value::String(s) => Value::Text(s),
+        | value => Value::Text(value.to_string()),
+    })
+}
+
+/// Formats a timestamp so it sorts lexicographically.
+fn timestamp(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn not_found() -> RepoError {
+    RepoError::NotFound("item does not exist".into())
+}
+
+#[async_trait::async_trait]
+impl<E> ReadRepo for SqliteOutboxRepo<E>
+where
+    E: EmitsEvents + Serialize + DeserializeOwned + Send + Sync + 'static,
+    E::Key: Serialize + PartialOrd + Send + Sync,
+{
+    type Entity = E;
+
+    async fn get(&self, key: &E::Key) -> RepoResult<E> {
+        let key = to_json(key)?;
+        let state = self
+            .run(move |conn| state(conn, &key))
+            .await?
+            .ok_or_else(not_found)?;
+
+        from_json(&state)
+    }
+
+    async fn get_page(&self, params: Pagination<E>) -> RepoResult<Vec<E>> {
+        let before_timestamp = timestamp(&params.before_timestamp);
+        let before_key = sort_key(&params.before_key)?;
+        let page_size = params.page_size as i64;
+
+        let states = self
+            .run(move |conn| {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT state FROM entities
+                         WHERE (created_at, sort_key) < (?1, ?2)
+                         ORDER BY created_at DESC, sort_key DESC LIMIT ?3",
+                    )
+                    .map_err(sql_error)?;
+                let rows = stmt
+                    .query_map(
+                        params![before_timestamp, before_key, page_size],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .map_err(sql_error)?;
+
+                rows.map(|state| state.map_err(sql_error))
+                    .collect::<RepoResult<Vec<_>>>()
+            })
+            .await?;
+
+        states.iter().map(|state| from_json(state)).collect()
+    }
+
+    async fn exists(&self, key: &E::Key) -> RepoResult<bool> {
+        let key = to_json(key)?;
+
+        self.run(move |conn| {
+            conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM entities WHERE key = ?1)",
+                [key],
+                |row| row.get(0),
+            )
+            .map_err(sql_error)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> WriteRepo for SqliteOutboxRepo<E>
+where
+    E: EmitsEvents + Serialize + DeserializeOwned + Send + Sync + 'static,
+    E::Key: Serialize + Sync,
+    E::Event: Serialize,
+{
+    type Entity = E;
+
+    async fn add(&self, mut item: E) -> RepoResult<E> {
+        let (row, events) = Self::encode(&mut item)?;
+
+        self.write(row, events, |tx, row, _| {
+            tx.execute(
+                "INSERT INTO entities (key, sort_key, created_at, state)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![row.key, row.sort_key, row.created_at, row.state],
+            )
+            .map_err(sql_error)?;
+
+            Ok(())
+        })
+        .await?;
+
+        Ok(item)
+    }
+
+    async fn update(&self, mut item: E) -> RepoResult<E> {
+        let (row, events) = Self::encode(&mut item)?;
+
+        self.write(row, events, |tx, row, stored| match stored {
+            | Some(_) => save(tx, row),
+            | None => Err(not_found()),
+        })
+        .await?;
+
+        Ok(item)
+    }
+
+    async fn upsert(&self, mut item: E) -> RepoResult<Upserted<E>> {
+        let (row, events) = Self::encode(&mut item)?;
+        let existed = self
+            .write(row, events, |tx, row, stored| {
+                save(tx, row).map(|_| stored.is_some())
+            })
+            .await?;
+
+        match existed {
+            | true => Ok(Upserted::Updated(item)),
+            | false => Ok(Upserted::Inserted(item)),
+        }
+    }
+
+    /// Updates an item in the data repository only if its currently stored
+    /// version satisfies `predicate`.
+    ///
+    /// As `predicate` cannot be moved to the blocking task that runs the
+    /// transaction, the stored item is read and checked first, then only
+    /// replaced if it did not change since, or checked again otherwise.
+    async fn update_if(
+        &self,
+        mut item: E,
+        predicate: &Predicate<'_, E>,
+    ) -> RepoResult<E> {
+        let (row, events) = Self::encode(&mut item)?;
+
+        loop {
+            let key = row.key.clone();
+            let seen = self
+                .run(move |conn| state(conn, &key))
+                .await?
+                .ok_or_else(not_found)?;
+
+            if !predicate(&from_json(&seen)?) {
+                return Err(RepoError::PreconditionFailed(
+                    "stored item does not satisfy the predicate".into(),
+                ));
+            }
+
+            let saved = self
+                .write(row.clone(), events.clone(), move |tx, row, stored| {
+                    match stored {
+                        | Some(stored) if stored == seen => {
+                            save(tx, row).map(|_| true)
+                        }
+                        // rolled back, as the item changed since it was seen
+                        | Some(_) => Err(RepoError::PreconditionFailed(
+                            "stored item changed".into(),
+                        )),
+                        | None => Err(not_found()),
+                    }
+                })
+                .await;
+
+            match saved {
+                | Ok(_) => return Ok(item),
+                | Err(RepoError::PreconditionFailed(_)) => continue,
+                | Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn remove(&self, key: &E::Key) -> RepoResult<()> {
+        let key = to_json(key)?;
+        let removed = self
+            .run(move |conn| {
+                conn.execute("DELETE FROM entities WHERE key = ?1", [key])
+                    .map_err(sql_error)
+            })
+            .await?;
+
+        match removed {
+            | 0 => Err(not_found()),
+            | _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> Outbox for SqliteOutboxRepo<E>
+where
+    E: EmitsEvents,
+    E::Event: DeserializeOwned + 'static,
+{
+    type Event = E::Event;
+
+    async fn pending(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<E::Event>>> {
+        self.run(move |conn| {
+            messages(conn, "published_at IS NULL AND dead_at IS NULL", limit)
+        })
+        .await
+    }
+
+    async fn mark_published(&self, ids: &[u64]) -> RepoResult<()> {
+        let ids = ids.to_vec();
+
+        self.run(move |conn| {
+            let tx = conn.transaction().map_err(sql_error)?;
+            let now = timestamp(&Utc::now());
+
+            for id in ids {
+                tx.execute(
+                    "UPDATE outbox SET published_at = ?1 WHERE id = ?2",
+                    params![now, id as i64],
+                )
+                .map_err(sql_error)?;
+            }
+
+            tx.commit().map_err(sql_error)
+        })
+        .await
+    }
+
+    async fn mark_failed(&self, id: u64) -> RepoResult<()> {
+        self.run(move |conn| {
+            conn.execute(
+                "UPDATE outbox SET attempts = attempts + 1 WHERE id = ?1",
+                [id as i64],
+            )
+            .map_err(sql_error)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn mark_dead(&self, id: u64) -> RepoResult<()> {
+        self.run(move |conn| {
+            conn.execute(
+                "UPDATE outbox SET attempts = attempts + 1, dead_at = ?1
+                 WHERE id = ?2",
+                params![timestamp(&Utc::now()), id as i64],
+            )
+            .map_err(sql_error)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn dead_letters(
+        &self,
+        limit: usize,
+    ) -> RepoResult<Vec<OutboxMessage<E::Event>>> {
+        self.run(move |conn| messages(conn, "dead_at IS NOT NULL", limit))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        outbox::testing::{Order, OrderEvent},
+        Entity,
+        Key,
+        ValueType,
+    };
+
+    #[tokio::test]
+    async fn unit_of_work_test() {
+        let repo = SqliteOutboxRepo::<Order>::open_in_memory().unwrap();
+        let mut order = repo.add(Order::place(1)).await.unwrap();
+
+        order.ship();
+        repo.update(order).await.unwrap();
+
+        assert!(matches!(
+            repo.add(Order::place(1)).await,
+            Err(RepoError::DuplicateValue(_))
+        ));
+        assert!(matches!(
+            repo.update(Order::place(2)).await,
+            Err(RepoError::NotFound(_))
+        ));
+        assert!(matches!(
+            repo.update_if(Order::place(1), &|o| o.status == "placed")
+                .await,
+            Err(RepoError::PreconditionFailed(_))
+        ));
+        assert!(repo.upsert(Order::place(2)).await.unwrap().is_inserted());
+
+        let events: Vec<_> = repo
+            .pending(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.event)
+            .collect();
+
+        assert_eq!(
+            vec![
+                OrderEvent::Placed(1),
+                OrderEvent::Shipped(1),
+                OrderEvent::Placed(2)
+            ],
+            events
+        );
+        assert_eq!("shipped", repo.get(&Key::new(1)).await.unwrap().status);
+    }
+
+    #[tokio::test]
+    async fn update_if_race_test() {
+        let repo = SqliteOutboxRepo::<Order>::open_in_memory().unwrap();
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        repo.add(Order::place(1)).await.unwrap();
+
+        let result = repo
+            .update_if(Order::place(1), &|stored| {
+                if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0
+                {
+                    // another writer ships the order once it was checked
+                    let mut shipped = Order::place(1);
+
+                    shipped.ship();
+                    repo.conn
+                        .lock()
+                        .unwrap()
+                        .execute(
+                            "UPDATE entities SET state = ?1",
+                            [to_json(&shipped).unwrap()],
+                        )
+                        .unwrap();
+                }
+
+                stored.status == "placed"
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepoError::PreconditionFailed(_))));
+        assert_eq!(2, calls.into_inner());
+        assert_eq!("shipped", repo.get(&Key::new(1)).await.unwrap().status);
+        assert_eq!(1, repo.pending(10).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn dead_letters_test() {
+        let repo = SqliteOutboxRepo::<Order>::open_in_memory().unwrap();
+
+        repo.add(Order::place(1)).await.unwrap();
+        repo.add(Order::place(2)).await.unwrap();
+
+        let pending = repo.pending(1).await.unwrap();
+
+        repo.mark_failed(pending[0].id).await.unwrap();
+        repo.mark_dead(pending[0].id).await.unwrap();
+
+        let pending = repo.pending(10).await.unwrap();
+        let dead_letters = repo.dead_letters(10).await.unwrap();
+
+        assert_eq!(1, pending.len());
+        assert_eq!(OrderEvent::Placed(2), pending[0].event);
+        assert_eq!(1, dead_letters.len());
+        assert_eq!(OrderEvent::Placed(1), dead_letters[0].event);
+        assert_eq!(2, dead_letters[0].attempts);
+    }
+
+    #[tokio::test]
+    async fn get_page_test() {
+        let repo = SqliteOutboxRepo::<Order>::open_in_memory().unwrap();
+        let created_at = Utc::now();
+
+        for id in [2, 10, 1, 3] {
+            repo.add(Order {
+                created_at,
+                ..Order::place(id)
+            })
+            .await
+            .unwrap();
+        }
+
+        let page = |before, page_size| {
+            repo.get_page(Pagination {
+                before_key: Key::new(before),
+                before_timestamp: created_at,
+                page_size,
+            })
+        };
+        let ids = |orders: Vec<Order>| -> Vec<u32> {
+            orders.iter().map(|o| *o.id().as_inner()).collect()
+        };
+
+        assert_eq!(vec![3, 2, 1], ids(page(10, 10).await.unwrap()));
+        assert_eq!(vec![3, 2], ids(page(10, 2).await.unwrap()));
+        assert_eq!(vec![1], ids(page(2, 10).await.unwrap()));
+    }
+
+    #[test]
+    fn sql_error_test() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO items VALUES (1, 'a');",
+        )
+        .unwrap();
+
+        let insert = |sql| sql_error(conn.execute(sql, []).unwrap_err());
+
+        assert!(matches!(
+            insert("INSERT INTO items VALUES (1, 'b')"),
+            RepoError::DuplicateValue(_)
+        ));
+        assert!(matches!(
+            insert("INSERT INTO items VALUES (2, NULL)"),
+            RepoError::Other(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn reopen_test() {
+        let path = std::env::temp_dir()
+            .join(format!("reddd-{}.sqlite", uuid::Uuid::new_v4()));
+
+        {
+            let repo = SqliteOutboxRepo::<Order>::open(&path).unwrap();
+
+            for id in 1..=3 {
+                repo.add(Order::place(id)).await.unwrap();
+            }
+
+            let pending = repo.pending(1).await.unwrap();
+
+            repo.mark_failed(pending[0].id).await.unwrap();
+            repo.mark_published(&[pending[0].id]).await.unwrap();
+            repo.remove(&Key::new(2)).await.unwrap();
+        }
+
+        let repo = SqliteOutboxRepo::<Order>::open(&path).unwrap();
+        let pending = repo.pending(10).await.unwrap();
+        let last = repo.get(&Key::new(3)).await.unwrap();
+
+        let page = repo
+            .get_page(Pagination {
+                before_key: last.id().clone(),
+                before_timestamp: *last.created_at(),
+                page_size: 10,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(2, pending.len());
+        assert_eq!(OrderEvent::Placed(2), pending[0].event);
+        assert_eq!(1, page.len());
+        assert_eq!(&Key::new(1), page[0].id());
+        assert!(!repo.exists(&Key::new(2)).await.unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+//! Test doubles shared by outbox tests.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use chrono::{DateTime, Utc};
+use reddd_macros::MutableEntity;
+use serde::{Deserialize, Serialize};
+
+use super::{EmitsEvents, EventBus, PublishError};
+use crate::domain::{Entity, Key, MutableEntity, ValueType};
+
+/// A sample entity that raises events to test outboxes with.
+#[derive(Clone, Debug, MutableEntity, Serialize, Deserialize)]
+pub(crate) struct Order {
+    pub(crate) id: Key<Order, u32>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) updated_at: DateTime<Utc>,
+    pub(crate) status: String,
+    #[serde(skip)]
+    pub(crate) events: Vec<OrderEvent>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum OrderEvent {
+    Placed(u32),
+    Shipped(u32),
+}
+
+impl Order {
+    pub(crate) fn place(id: u32) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Key::new(id),
+            created_at: now,
+            updated_at: now,
+            status: "placed".into(),
+            events: vec![OrderEvent::Placed(id)],
+        }
+    }
+
+    pub(crate) fn ship(&mut self) {
+        self.status = "shipped".into();
+        self.events.push(OrderEvent::Shipped(*self.id.as_inner()));
+        self.touch();
+    }
+}
+
+impl EmitsEvents for Order {
+    type Event = OrderEvent;
+
+    fn take_events(&mut self) -> Vec<Self::Event> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// An event bus that records published events, and can be made to fail.
+pub(crate) struct RecordingBus<Ev> {
+    published: Mutex<Vec<Ev>>,
+    failures: AtomicUsize,
+    failing: Mutex<Vec<Ev>>,
+}
+
+impl<Ev: Clone> RecordingBus<Ev> {
+    pub(crate) fn new() -> Self {
+        Self {
+            published: Default::default(),
+            failures: Default::default(),
+            failing: Default::default(),
+        }
+    }
+
+    /// Gets the events published so far, in order.
+    pub(crate) fn published(&self) -> Vec<Ev> {
+        self.published.lock().unwrap().clone()
+    }
+
+    /// Makes the next `count` publications fail.
+    pub(crate) fn fail_next(&self, count: usize) {
+        self.failures.store(count, Ordering::SeqCst);
+    }
+
+    /// Makes all publications of `event` fail.
+    pub(crate) fn fail_on(&self, event: Ev) {
+        self.failing.lock().unwrap().push(event);
+    }
+}
+
+#[async_trait::async_trait]
+impl<Ev: Clone + PartialEq + Send + Sync> EventBus for RecordingBus<Ev> {
+    type Event = Ev;
+
+    async fn publish(&self, event: &Ev) -> Result<(), PublishError> {
+        let failed = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| {
+                f.checked_sub(1)
+            })
+            .is_ok();
+
+        if failed || self.failing.lock().unwrap().contains(event) {
+            return Err("bus is unavailable".into());
+        }
+
+        self.published.lock().unwrap().push(event.clone());
+
+        Ok(())
+    }
+}
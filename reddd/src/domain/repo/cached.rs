@@ -0,0 +1,626 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+        MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{
+    error::RepoResult,
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::{Entity, MutableEntity};
+
+/// A type alias to simplify referring to keys of a repository entity.
+type RepoKey<R> = <<R as ReadRepo>::Entity as Entity>::Key;
+
+/// A struct that holds [`CachedRepo`] configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct CacheConfig {
+    /// The duration after which cached entries are considered stale.
+    pub ttl: Duration,
+
+    /// The maximum number of entries to keep in the cache.
+    ///
+    /// When the cache is full, the least recently used entries are evicted.
+    pub capacity: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            capacity: 1024,
+        }
+    }
+}
+
+/// A struct that holds [`CachedRepo`] usage statistics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of reads that were served from the cache.
+    pub hits: u64,
+
+    /// The number of reads that were forwarded to the inner repository.
+    pub misses: u64,
+
+    /// The number of entries that were evicted to respect the capacity.
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Gets the ratio of reads that were served from the cache, or `0.0` if
+    /// no reads were made.
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            | 0 => 0.0,
+            | total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// The cached entries, along with their recency order and the reads that are
+/// expected to fill them.
+struct CacheState<K, E> {
+    entries: HashMap<K, CacheEntry<E>>,
+    recency: BTreeMap<u64, K>,
+    fills: HashMap<K, Fill>,
+    ticks: u64,
+}
+
+/// A cached value, along with its expiry instant and the tick at which it
+/// was last used.
+struct CacheEntry<E> {
+    value: CachedValue<E>,
+    expires_at: Instant,
+    used_at: u64,
+}
+
+/// The reads of a single key that missed the cache, which are allowed to
+/// fill it as long as the key was not invalidated since.
+struct Fill {
+    id: u64,
+    readers: usize,
+}
+
+/// A read that missed the cache, which caches the value it is
+/// [`fill`](Self::fill)ed with when dropped, unless its key was invalidated
+/// in the meantime, as the value might have been read before a write
+/// completed.
+struct FillTicket<'a, R>
+where
+    R: ReadRepo,
+    R::Entity: Clone,
+    RepoKey<R>: Clone + Eq + Hash,
+{
+    repo: &'a CachedRepo<R>,
+    key: RepoKey<R>,
+    id: u64,
+    value: Option<CachedValue<R::Entity>>,
+}
+
+/// A value that can be cached for a single key.
+enum CachedValue<E> {
+    /// The item itself, which also implies its existence.
+    Item(E),
+
+    /// Only whether the item exists or not.
+    Exists(bool),
+}
+
+/// A repository decorator that caches [`get`](ReadRepo::get) and
+/// [`exists`](ReadRepo::exists) results of an inner repository, using entity
+/// keys as cache keys.
+///
+/// Cached entries expire after [`CacheConfig::ttl`], and are invalidated
+/// whenever the item is written through the [`WriteRepo`] implementation of
+/// this type. Results of reads that raced with writes of the same item are
+/// not cached, so stale items are never kept. Writes made directly to the inner
+/// repository are **not** observed until the cached entries expire.
+///
+/// # Example
+///
+/// ```ignore
+/// let users_repo = CachedRepo::new(users_repo, CacheConfig::default());
+///
+/// let user = users_repo.get(&user_id).await?; // miss
+/// let user = users_repo.get(&user_id).await?; // hit
+///
+/// assert_eq!(1, users_repo.stats().hits);
+/// ```
+pub struct CachedRepo<R: ReadRepo> {
+    inner: R,
+    config: CacheConfig,
+    state: Mutex<CacheState<RepoKey<R>, R::Entity>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<R> CachedRepo<R>
+where
+    R: ReadRepo,
+    R::Entity: Clone,
+    RepoKey<R>: Clone + Eq + Hash,
+{
+    /// Creates a new caching repository on top of `inner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The repository to cache results of.
+    /// * `config` - The cache configuration.
+    pub fn new(inner: R, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                fills: HashMap::new(),
+                ticks: 0,
+            }),
+            hits: Default::default(),
+            misses: Default::default(),
+            evictions: Default::default(),
+        }
+    }
+
+    /// Gets a reference to the inner repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a snapshot of the cache usage statistics.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Gets the number of currently cached entries, including expired ones
+    /// that were not evicted yet.
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Checks whether the cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the cached entry of a single item, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item to invalidate.
+    pub fn invalidate(&self, key: &RepoKey<R>) {
+        let mut state = self.state();
+
+        if let Some(entry) = state.entries.remove(key) {
+            state.recency.remove(&entry.used_at);
+        }
+
+        state.fills.remove(key);
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) {
+        let mut state = self.state();
+
+        state.entries.clear();
+        state.recency.clear();
+        state.fills.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState<RepoKey<R>, R::Entity>> {
+        self.state.lock().unwrap()
+    }
+
+    /// Looks a key up in the cache, and maps its value if it is still fresh.
+    ///
+    /// On misses, a ticket is returned instead, which must be filled with
+    /// the value read from the inner repository.
+    fn lookup<T>(
+        &self,
+        key: &RepoKey<R>,
+        map: impl FnOnce(&CachedValue<R::Entity>) -> Option<T>,
+    ) -> Result<T, FillTicket<'_, R>> {
+        let mut state = self.state();
+        let now = Instant::now();
+        let state = &mut *state;
+
+        if let Some(entry) = state.entries.get_mut(key) {
+            state.recency.remove(&entry.used_at);
+
+            match entry.expires_at > now {
+                | true => {
+                    state.ticks += 1;
+                    entry.used_at = state.ticks;
+                    state.recency.insert(entry.used_at, key.clone());
+                }
+                | false => {
+                    state.entries.remove(key);
+                }
+            }
+        }
+
+        let value = state.entries.get(key).and_then(|e| map(&e.value));
+
+        match value {
+            | Some(value) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+
+                Ok(value)
+            }
+            | None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                state.ticks += 1;
+
+                let id = state.ticks;
+                let fill = state
+                    .fills
+                    .entry(key.clone())
+                    .or_insert(Fill { id, readers: 0 });
+
+                fill.readers += 1;
+
+                Err(FillTicket {
+                    repo: self,
+                    key: key.clone(),
+                    id: fill.id,
+                    value: None,
+                })
+            }
+        }
+    }
+
+    /// Releases a ticket of a read that missed the cache, and caches the
+    /// value it was filled with, if its key was not invalidated since.
+    fn release(&self, ticket: &mut FillTicket<'_, R>) {
+        let mut state = self.state();
+        let state = &mut *state;
+
+        match state.fills.get_mut(&ticket.key) {
+            | Some(fill) if fill.id == ticket.id => {
+                fill.readers -= 1;
+
+                if fill.readers == 0 {
+                    state.fills.remove(&ticket.key);
+                }
+            }
+            | _ => return,
+        }
+
+        let Some(value) = ticket.value.take() else {
+            return;
+        };
+
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        if let Some(entry) = state.entries.remove(&ticket.key) {
+            state.recency.remove(&entry.used_at);
+        }
+
+        while state.entries.len() >= self.config.capacity {
+            let Some((_, lru)) = state.recency.pop_first() else {
+                break;
+            };
+
+            state.entries.remove(&lru);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        state.ticks += 1;
+        state.recency.insert(state.ticks, ticket.key.clone());
+        state.entries.insert(
+            ticket.key.clone(),
+            CacheEntry {
+                value,
+                expires_at: Instant::now() + self.config.ttl,
+                used_at: state.ticks,
+            },
+        );
+    }
+}
+
+impl<R> FillTicket<'_, R>
+where
+    R: ReadRepo,
+    R::Entity: Clone,
+    RepoKey<R>: Clone + Eq + Hash,
+{
+    /// Fills the ticket with the value read from the inner repository.
+    fn fill(mut self, value: CachedValue<R::Entity>) {
+        self.value = Some(value);
+    }
+}
+
+impl<R> Drop for FillTicket<'_, R>
+where
+    R: ReadRepo,
+    R::Entity: Clone,
+    RepoKey<R>: Clone + Eq + Hash,
+{
+    fn drop(&mut self) {
+        self.repo.release(self);
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> ReadRepo for CachedRepo<R>
+where
+    R: ReadRepo,
+    R::Entity: Clone,
+    RepoKey<R>: Clone + Eq + Hash + Send + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn get(&self, key: &RepoKey<R>) -> RepoResult<Self::Entity> {
+        let cached = self.lookup(key, |value| match value {
+            | CachedValue::Item(item) => Some(item.clone()),
+            | CachedValue::Exists(_) => None,
+        });
+
+        let ticket = match cached {
+            | Ok(item) => return Ok(item),
+            | Err(ticket) => ticket,
+        };
+
+        let item = self.inner.get(key).await?;
+
+        ticket.fill(CachedValue::Item(item.clone()));
+
+        Ok(item)
+    }
+
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> RepoResult<Vec<Self::Entity>> {
+        self.inner.get_page(params).await
+    }
+
+    async fn exists(&self, key: &RepoKey<R>) -> RepoResult<bool> {
+        let cached = self.lookup(key, |value| match value {
+            | CachedValue::Item(_) => Some(true),
+            | CachedValue::Exists(exists) => Some(*exists),
+        });
+
+        let ticket = match cached {
+            | Ok(exists) => return Ok(exists),
+            | Err(ticket) => ticket,
+        };
+
+        let exists = self.inner.exists(key).await?;
+
+        ticket.fill(CachedValue::Exists(exists));
+
+        Ok(exists)
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> WriteRepo for CachedRepo<R>
+where
+    R: ReadRepo + WriteRepo<Entity = <R as ReadRepo>::Entity>,
+    <R as ReadRepo>::Entity: MutableEntity + Clone,
+    RepoKey<R>: Clone + Eq + Hash + Send + Sync,
+{
+    type Entity = <R as ReadRepo>::Entity;
+
+    async fn add(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        let key = item.id().clone();
+        let result = self.inner.add(item).await;
+
+        self.invalidate(&key);
+
+        result
+    }
+
+    async fn update(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        let key = item.id().clone();
+        let result = self.inner.update(item).await;
+
+        self.invalidate(&key);
+
+        result
+    }
+
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> RepoResult<Upserted<Self::Entity>> {
+        let key = item.id().clone();
+        let result = self.inner.upsert(item).await;
+
+        self.invalidate(&key);
+
+        result
+    }
+
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> RepoResult<Self::Entity> {
+        let key = item.id().clone();
+        let result = self.inner.update_if(item, predicate).await;
+
+        self.invalidate(&key);
+
+        result
+    }
+
+    async fn remove(&self, key: &RepoKey<R>) -> RepoResult<()> {
+        let result = self.inner.remove(key).await;
+
+        self.invalidate(key);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        repo::testing::{MemRepo, TestItem},
+        Key,
+    };
+
+    fn cached(config: CacheConfig) -> CachedRepo<MemRepo<TestItem>> {
+        CachedRepo::new(MemRepo::new(), config)
+    }
+
+    #[tokio::test]
+    async fn get_hit_test() {
+        let repo = cached(CacheConfig::default());
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+        assert!(repo.exists(item.id()).await.unwrap());
+
+        assert_eq!(2, repo.inner().calls());
+        assert_eq!(
+            CacheStats {
+                hits: 2,
+                misses: 1,
+                evictions: 0,
+            },
+            repo.stats()
+        );
+    }
+
+    #[tokio::test]
+    async fn exists_hit_test() {
+        let repo = cached(CacheConfig::default());
+        let key = Key::new(1);
+
+        assert!(!repo.exists(&key).await.unwrap());
+        assert!(!repo.exists(&key).await.unwrap());
+        assert!(repo.get(&key).await.is_err());
+
+        assert_eq!(2, repo.inner().calls());
+        assert_eq!(1, repo.stats().hits);
+        assert_eq!(2, repo.stats().misses);
+    }
+
+    #[tokio::test]
+    async fn write_invalidation_test() {
+        let repo = cached(CacheConfig::default());
+        let mut item = repo.add(TestItem::new(1, "one")).await.unwrap();
+
+        assert!(repo.exists(item.id()).await.unwrap());
+        assert_eq!(1, repo.len());
+
+        item.value = "updated".into();
+        repo.update(item.clone()).await.unwrap();
+
+        assert!(repo.is_empty());
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+        assert_eq!(1, repo.len());
+
+        repo.remove(item.id()).await.unwrap();
+
+        assert!(repo.is_empty());
+        assert!(!repo.exists(item.id()).await.unwrap());
+        assert_eq!(0, repo.stats().hits);
+    }
+
+    #[tokio::test]
+    async fn stale_fill_test() {
+        let repo = cached(CacheConfig::default());
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+        let other = repo.add(TestItem::new(2, "two")).await.unwrap();
+
+        // misses that read `item` and `other`, then race with an update
+        let ticket = repo.lookup(item.id(), |_| Some(())).unwrap_err();
+        let other_ticket = repo.lookup(other.id(), |_| Some(())).unwrap_err();
+        let stale = repo.inner().get(item.id()).await.unwrap();
+        let mut updated = item.clone();
+
+        updated.value = "updated".into();
+        repo.update(updated.clone()).await.unwrap();
+        ticket.fill(CachedValue::Item(stale));
+        other_ticket.fill(CachedValue::Item(other.clone()));
+
+        assert_eq!(1, repo.len());
+        assert_eq!(other, repo.get(other.id()).await.unwrap());
+        assert_eq!(1, repo.stats().hits);
+        assert_eq!(updated, repo.get(item.id()).await.unwrap());
+        assert_eq!(2, repo.len());
+    }
+
+    #[tokio::test]
+    async fn cancelled_fill_test() {
+        let repo = cached(CacheConfig::default());
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+
+        drop(repo.lookup(item.id(), |_| Some(())).unwrap_err());
+
+        assert!(repo.is_empty());
+        assert!(repo.state().fills.is_empty());
+
+        repo.get(item.id()).await.unwrap();
+
+        assert_eq!(1, repo.len());
+        assert!(repo.state().fills.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ttl_test() {
+        let repo = cached(CacheConfig {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+
+        repo.get(item.id()).await.unwrap();
+        repo.get(item.id()).await.unwrap();
+
+        assert_eq!(0, repo.stats().hits);
+        assert_eq!(2, repo.stats().misses);
+    }
+
+    #[tokio::test]
+    async fn capacity_test() {
+        let repo = cached(CacheConfig {
+            capacity: 2,
+            ..Default::default()
+        });
+
+        for i in 0..4 {
+            let item = repo.add(TestItem::new(i, "item")).await.unwrap();
+
+            repo.get(item.id()).await.unwrap();
+        }
+
+        assert_eq!(2, repo.len());
+        assert_eq!(2, repo.stats().evictions);
+
+        repo.get(&Key::new(2)).await.unwrap();
+        repo.get(&Key::new(0)).await.unwrap();
+
+        assert_eq!(1, repo.stats().hits);
+        assert_eq!(3, repo.stats().evictions);
+
+        // `3` was the least recently used entry, and was evicted for `0`
+        repo.get(&Key::new(2)).await.unwrap();
+        repo.get(&Key::new(3)).await.unwrap();
+
+        assert_eq!(2, repo.stats().hits);
+    }
+}
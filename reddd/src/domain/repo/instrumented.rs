@@ -0,0 +1,235 @@
+use std::{any::type_name, fmt::Debug, future::Future};
+
+use tracing::{field, Instrument, Span};
+
+use super::{
+    error::RepoResult,
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::Entity;
+
+/// A repository decorator that emits a [`tracing`] span for each operation of
+/// an inner repository.
+///
+/// Spans are named `repo`, and are labeled with the entity type name as the
+/// `entity` field, as well as the `otel.name` field (e.g. `my::User::get`)
+/// for OpenTelemetry exporters. Spans also record the operation name, the
+/// key of the item involved or the page size, the outcome (`ok` or `error`),
+/// and the [`RepoError`](super::error::RepoError) variant on failure.
+///
+/// # Example
+///
+/// ```ignore
+/// let users_repo = InstrumentedRepo::new(users_repo);
+///
+/// // emits a span with `operation = "get"` and `key = <user_id>`
+/// let user = users_repo.get(&user_id).await?;
+/// ```
+pub struct InstrumentedRepo<R> {
+    inner: R,
+}
+
+impl<R> InstrumentedRepo<R> {
+    /// Creates a new instrumented repository on top of `inner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The repository to instrument.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Gets a reference to the inner repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+/// Creates a span of a single repository operation.
+fn repo_span<E>(operation: &'static str) -> Span {
+    let entity = type_name::<E>();
+
+    tracing::info_span!(
+        "repo",
+        otel.name = format!("{entity}::{operation}"),
+        entity,
+        operation,
+        key = field::Empty,
+        page_size = field::Empty,
+        outcome = field::Empty,
+        error = field::Empty,
+    )
+}
+
+/// Runs `fut` within `span`, and records its outcome.
+async fn instrument<T>(
+    span: Span,
+    fut: impl Future<Output = RepoResult<T>>,
+) -> RepoResult<T> {
+    let result = fut.instrument(span.clone()).await;
+
+    match result {
+        | Ok(_) => span.record("outcome", "ok"),
+        | Err(ref err) => {
+            span.record("outcome", "error").record("error", err.kind())
+        }
+    };
+
+    result
+}
+
+/// Creates a span of a single repository operation on an item.
+fn key_span<E: Entity>(operation: &'static str, key: &E::Key) -> Span
+where
+    E::Key: Debug,
+{
+    let span = repo_span::<E>(operation);
+
+    span.record("key", field::debug(key));
+    span
+}
+
+#[async_trait::async_trait]
+impl<R> ReadRepo for InstrumentedRepo<R>
+where
+    R: ReadRepo,
+    <R::Entity as Entity>::Key: Debug + Send + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn get(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<Self::Entity> {
+        let span = key_span::<R::Entity>("get", key);
+
+        instrument(span, self.inner.get(key)).await
+    }
+
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> RepoResult<Vec<Self::Entity>> {
+        let span = repo_span::<R::Entity>("get_page");
+
+        span.record("page_size", params.page_size);
+
+        instrument(span, self.inner.get_page(params)).await
+    }
+
+    async fn exists(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<bool> {
+        let span = key_span::<R::Entity>("exists", key);
+
+        instrument(span, self.inner.exists(key)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> WriteRepo for InstrumentedRepo<R>
+where
+    R: WriteRepo,
+    <R::Entity as Entity>::Key: Debug + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn add(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        let span = key_span::<R::Entity>("add", item.id());
+
+        instrument(span, self.inner.add(item)).await
+    }
+
+    async fn update(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        let span = key_span::<R::Entity>("update", item.id());
+
+        instrument(span, self.inner.update(item)).await
+    }
+
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> RepoResult<Upserted<Self::Entity>> {
+        let span = key_span::<R::Entity>("upsert", item.id());
+
+        instrument(span, self.inner.upsert(item)).await
+    }
+
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> RepoResult<Self::Entity> {
+        let span = key_span::<R::Entity>("update_if", item.id());
+
+        instrument(span, self.inner.update_if(item, predicate)).await
+    }
+
+    async fn remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<()> {
+        let span = key_span::<R::Entity>("remove", key);
+
+        instrument(span, self.inner.remove(key)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        repo::testing::{MemRepo, SpanCapture, TestItem},
+        Key,
+    };
+
+    #[tokio::test]
+    async fn read_spans_test() {
+        let capture = SpanCapture::default();
+        let _guard = capture.install();
+        let repo = InstrumentedRepo::new(MemRepo::<TestItem>::new());
+        let item = TestItem::new(7, "seven");
+
+        repo.add(item.clone()).await.unwrap();
+        repo.get(item.id()).await.unwrap();
+        repo.get_page(Pagination {
+            before_key: Key::new(0),
+            before_timestamp: chrono::Utc::now(),
+            page_size: 25,
+        })
+        .await
+        .unwrap();
+
+        let output = capture.output();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(3, lines.len());
+        assert!(lines[0].contains("operation=\"add\""));
+        assert!(lines[0].contains("entity=\"reddd::domain::repo::testing"));
+        assert!(lines[1].contains("operation=\"get\""));
+        assert!(lines[1].contains("key=TypedValue(7"));
+        assert!(lines[1].contains("outcome=\"ok\""));
+        assert!(lines[2].contains("operation=\"get_page\""));
+        assert!(lines[2].contains("page_size=25"));
+    }
+
+    #[tokio::test]
+    async fn error_span_test() {
+        let capture = SpanCapture::default();
+        let _guard = capture.install();
+        let repo = InstrumentedRepo::new(MemRepo::<TestItem>::new());
+
+        assert!(repo.remove(&Key::new(3)).await.is_err());
+
+        let output = capture.output();
+
+        assert!(output.contains("operation=\"remove\""));
+        assert!(output.contains("outcome=\"error\""));
+        assert!(output.contains("error=\"NotFound\""));
+    }
+}
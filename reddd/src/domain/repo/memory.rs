@@ -0,0 +1,345 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Mutex, MutexGuard},
+};
+
+use super::{
+    error::{RepoError, RepoResult},
+    DeletedItems,
+    Pagination,
+    Predicate,
+    ReadRepo,
+    SoftDeleteRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::{Entity, SoftDeletableEntity};
+
+/// A repository that keeps soft-deletable entities in memory.
+///
+/// Soft-deleted items are kept as tombstones, which are hidden from
+/// [`ReadRepo`] operations, as well as from [`update`](WriteRepo::update)
+/// and [`update_if`](WriteRepo::update_if), until they are restored.
+///
+/// This repository is mostly useful for tests and local development, as
+/// entities are lost once the repository is dropped.
+pub struct InMemorySoftDeleteRepo<E: Entity> {
+    items: Mutex<HashMap<E::Key, E>>,
+}
+
+impl<E: Entity> InMemorySoftDeleteRepo<E> {
+    /// Creates a new empty repository.
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn items(&self) -> MutexGuard<'_, HashMap<E::Key, E>> {
+        self.items.lock().unwrap()
+    }
+}
+
+impl<E: Entity> Default for InMemorySoftDeleteRepo<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn not_found() -> RepoError {
+    RepoError::NotFound("item does not exist".into())
+}
+
+/// Checks whether `item` is visible when soft-deleted items are treated as
+/// specified by `deleted`.
+fn is_visible<E: SoftDeletableEntity>(item: &E, deleted: DeletedItems) -> bool {
+    match deleted {
+        | DeletedItems::Exclude => !item.is_deleted(),
+        | DeletedItems::Include => true,
+        | DeletedItems::Only => item.is_deleted(),
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> ReadRepo for InMemorySoftDeleteRepo<E>
+where
+    E: SoftDeletableEntity + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + PartialOrd + Send + Sync,
+{
+    type Entity = E;
+
+    async fn get(&self, key: &E::Key) -> RepoResult<E> {
+        self.get_with(key, DeletedItems::Exclude).await
+    }
+
+    async fn get_page(&self, params: Pagination<E>) -> RepoResult<Vec<E>> {
+        self.get_page_with(params, DeletedItems::Exclude).await
+    }
+
+    async fn exists(&self, key: &E::Key) -> RepoResult<bool> {
+        Ok(self.items().get(key).is_some_and(|i| !i.is_deleted()))
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> WriteRepo for InMemorySoftDeleteRepo<E>
+where
+    E: SoftDeletableEntity + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + Send + Sync,
+{
+    type Entity = E;
+
+    async fn add(&self, item: E) -> RepoResult<E> {
+        let mut items = self.items();
+
+        if items.contains_key(item.id()) {
+            return Err(RepoError::DuplicateValue("item exists".into()));
+        }
+
+        items.insert(item.id().clone(), item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(&self, item: E) -> RepoResult<E> {
+        self.update_if(item, &|_| true).await
+    }
+
+    async fn upsert(&self, item: E) -> RepoResult<Upserted<E>> {
+        let stored = self.items().insert(item.id().clone(), item.clone());
+
+        match stored {
+            | Some(stored) if !stored.is_deleted() => {
+                Ok(Upserted::Updated(item))
+            }
+            | _ => Ok(Upserted::Inserted(item)),
+        }
+    }
+
+    async fn update_if(
+        &self,
+        item: E,
+        predicate: &Predicate<'_, E>,
+    ) -> RepoResult<E> {
+        let mut items = self.items();
+        let stored = items
+            .get_mut(item.id())
+            .filter(|i| !i.is_deleted())
+            .ok_or_else(not_found)?;
+
+        if !predicate(stored) {
+            return Err(RepoError::PreconditionFailed(
+                "stored item does not satisfy the predicate".into(),
+            ));
+        }
+
+        *stored = item.clone();
+
+        Ok(item)
+    }
+
+    async fn remove(&self, key: &E::Key) -> RepoResult<()> {
+        self.items().remove(key).map(|_| ()).ok_or_else(not_found)
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> SoftDeleteRepo for InMemorySoftDeleteRepo<E>
+where
+    E: SoftDeletableEntity + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + PartialOrd + Send + Sync,
+{
+    type Entity = E;
+
+    async fn get_with(
+        &self,
+        key: &E::Key,
+        deleted: DeletedItems,
+    ) -> RepoResult<E> {
+        self.items()
+            .get(key)
+            .filter(|i| is_visible(*i, deleted))
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    async fn get_page_with(
+        &self,
+        params: Pagination<E>,
+        deleted: DeletedItems,
+    ) -> RepoResult<Vec<E>> {
+        let mut items: Vec<_> = self
+            .items()
+            .values()
+            .filter(|i| {
+                is_visible(*i, deleted)
+                    && (i.created_at(), i.id())
+                        < (&params.before_timestamp, &params.before_key)
+            })
+            .cloned()
+            .collect();
+
+        items.sort_by(|a, b| {
+            (b.created_at(), b.id())
+                .partial_cmp(&(a.created_at(), a.id()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        items.truncate(params.page_size);
+
+        Ok(items)
+    }
+
+    async fn soft_remove(&self, key: &E::Key) -> RepoResult<()> {
+        self.items()
+            .get_mut(key)
+            .filter(|i| !i.is_deleted())
+            .ok_or_else(not_found)?
+            .mark_deleted();
+
+        Ok(())
+    }
+
+    async fn restore(&self, key: &E::Key) -> RepoResult<E> {
+        let mut items = self.items();
+        let item = items
+            .get_mut(key)
+            .filter(|i| i.is_deleted())
+            .ok_or_else(not_found)?;
+
+        item.mark_restored();
+
+        Ok(item.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use reddd_macros::MutableEntity;
+
+    use super::*;
+    use crate::domain::{Key, MutableEntity, ValueType};
+
+    #[derive(Clone, Debug, PartialEq, MutableEntity)]
+    struct Note {
+        id: Key<Note, u32>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        #[deleted_at_field]
+        deleted_at: Option<DateTime<Utc>>,
+    }
+
+    impl Note {
+        fn new(id: u32) -> Self {
+            let now = Utc::now();
+
+            Self {
+                id: Key::new(id),
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            }
+        }
+    }
+
+    async fn repo() -> InMemorySoftDeleteRepo<Note> {
+        let repo = InMemorySoftDeleteRepo::new();
+
+        for id in 1..=3 {
+            repo.add(Note::new(id)).await.unwrap();
+        }
+
+        repo
+    }
+
+    fn ids(notes: &[Note]) -> Vec<u32> {
+        notes.iter().map(|n| *n.id().as_inner()).collect()
+    }
+
+    #[tokio::test]
+    async fn soft_remove_test() {
+        let repo = repo().await;
+        let key = Key::new(2);
+
+        repo.soft_remove(&key).await.unwrap();
+
+        assert!(matches!(repo.get(&key).await, Err(RepoError::NotFound(_))));
+        assert!(!repo.exists(&key).await.unwrap());
+        assert!(matches!(
+            repo.soft_remove(&key).await,
+            Err(RepoError::NotFound(_))
+        ));
+        assert!(matches!(
+            repo.update(Note::new(2)).await,
+            Err(RepoError::NotFound(_))
+        ));
+        assert!(matches!(
+            repo.add(Note::new(2)).await,
+            Err(RepoError::DuplicateValue(_))
+        ));
+
+        let deleted = repo.get_with(&key, DeletedItems::Only).await.unwrap();
+
+        assert!(deleted.is_deleted());
+        assert_eq!(deleted.deleted_at(), Some(deleted.updated_at()));
+    }
+
+    #[tokio::test]
+    async fn restore_test() {
+        let repo = repo().await;
+        let key = Key::new(1);
+
+        assert!(matches!(
+            repo.restore(&key).await,
+            Err(RepoError::NotFound(_))
+        ));
+
+        repo.soft_remove(&key).await.unwrap();
+
+        let restored = repo.restore(&key).await.unwrap();
+
+        assert!(!restored.is_deleted());
+        assert_eq!(restored, repo.get(&key).await.unwrap());
+        assert!(matches!(
+            repo.get_with(&key, DeletedItems::Only).await,
+            Err(RepoError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_page_with_test() {
+        let repo = repo().await;
+        let params = || Pagination {
+            before_key: Key::new(0),
+            before_timestamp: Utc::now(),
+            page_size: 10,
+        };
+        let page = |deleted| repo.get_page_with(params(), deleted);
+
+        repo.soft_remove(&Key::new(2)).await.unwrap();
+
+        assert_eq!(vec![3, 1], ids(&repo.get_page(params()).await.unwrap()));
+        assert_eq!(
+            vec![3, 1],
+            ids(&page(DeletedItems::Exclude).await.unwrap())
+        );
+        assert_eq!(
+            vec![3, 2, 1],
+            ids(&page(DeletedItems::Include).await.unwrap())
+        );
+        assert_eq!(vec![2], ids(&page(DeletedItems::Only).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn upsert_test() {
+        let repo = repo().await;
+
+        repo.soft_remove(&Key::new(1)).await.unwrap();
+
+        assert!(repo.upsert(Note::new(1)).await.unwrap().is_inserted());
+        assert!(repo.upsert(Note::new(2)).await.unwrap().is_updated());
+        assert!(repo.upsert(Note::new(4)).await.unwrap().is_inserted());
+        assert!(repo.exists(&Key::new(1)).await.unwrap());
+    }
+}
@@ -0,0 +1,244 @@
+use std::{any::type_name, future::Future, time::Instant};
+
+use super::{
+    error::RepoResult,
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::Entity;
+
+/// A repository decorator that records statistics of each operation of an
+/// inner repository through the [`metrics`] facade.
+///
+/// The following metrics are recorded, all labeled with the entity type name
+/// as `entity` and the operation name (e.g. `get`) as `operation`:
+///
+/// * `repo_operations_total` - A counter of operations, additionally labeled
+///   with the `outcome` (`ok` or `error`).
+/// * `repo_errors_total` - A counter of failed operations, additionally labeled
+///   with the [`RepoError`](super::error::RepoError) variant as `error`.
+/// * `repo_operation_duration_seconds` - A histogram of operation latencies.
+///
+/// # Example
+///
+/// ```ignore
+/// let users_repo = MeteredRepo::new(users_repo);
+///
+/// // increments `repo_operations_total{operation="get", ...}`
+/// let user = users_repo.get(&user_id).await?;
+/// ```
+pub struct MeteredRepo<R> {
+    inner: R,
+}
+
+impl<R> MeteredRepo<R> {
+    /// Creates a new metered repository on top of `inner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The repository to record statistics of.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Gets a reference to the inner repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+/// Runs `fut`, and records its statistics as a `operation` on `E` entities.
+async fn measure<E, T>(
+    operation: &'static str,
+    fut: impl Future<Output = RepoResult<T>>,
+) -> RepoResult<T> {
+    let entity = type_name::<E>();
+    let started_at = Instant::now();
+    let result = fut.await;
+
+    metrics::histogram!(
+        "repo_operation_duration_seconds",
+        "entity" => entity,
+        "operation" => operation,
+    )
+    .record(started_at.elapsed());
+
+    let outcome = match result {
+        | Ok(_) => "ok",
+        | Err(ref err) => {
+            metrics::counter!(
+                "repo_errors_total",
+                "entity" => entity,
+                "operation" => operation,
+                "error" => err.kind(),
+            )
+            .increment(1);
+
+            "error"
+        }
+    };
+
+    metrics::counter!(
+        "repo_operations_total",
+        "entity" => entity,
+        "operation" => operation,
+        "outcome" => outcome,
+    )
+    .increment(1);
+
+    result
+}
+
+#[async_trait::async_trait]
+impl<R> ReadRepo for MeteredRepo<R>
+where
+    R: ReadRepo,
+    <R::Entity as Entity>::Key: Send + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn get(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<Self::Entity> {
+        measure::<R::Entity, _>("get", self.inner.get(key)).await
+    }
+
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> RepoResult<Vec<Self::Entity>> {
+        measure::<R::Entity, _>("get_page", self.inner.get_page(params)).await
+    }
+
+    async fn exists(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<bool> {
+        measure::<R::Entity, _>("exists", self.inner.exists(key)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> WriteRepo for MeteredRepo<R>
+where
+    R: WriteRepo,
+    <R::Entity as Entity>::Key: Sync,
+{
+    type Entity = R::Entity;
+
+    async fn add(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        measure::<R::Entity, _>("add", self.inner.add(item)).await
+    }
+
+    async fn update(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        measure::<R::Entity, _>("update", self.inner.update(item)).await
+    }
+
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> RepoResult<Upserted<Self::Entity>> {
+        measure::<R::Entity, _>("upsert", self.inner.upsert(item)).await
+    }
+
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> RepoResult<Self::Entity> {
+        measure::<R::Entity, _>(
+            "update_if",
+            self.inner.update_if(item, predicate),
+        )
+        .await
+    }
+
+    async fn remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<()> {
+        measure::<R::Entity, _>("remove", self.inner.remove(key)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        repo::testing::{MemRepo, MetricsCapture, TestItem},
+        Key,
+    };
+
+    #[tokio::test]
+    async fn operations_test() {
+        let capture = MetricsCapture::new();
+        let _guard = capture.install();
+        let repo = MeteredRepo::new(MemRepo::<TestItem>::new());
+        let item = TestItem::new(1, "one");
+
+        repo.add(item.clone()).await.unwrap();
+        repo.get(item.id()).await.unwrap();
+        repo.get(item.id()).await.unwrap();
+        repo.exists(item.id()).await.unwrap();
+
+        let metrics = capture.snapshot();
+        let ok = ("outcome", "ok");
+
+        assert_eq!(
+            1,
+            metrics
+                .counter("repo_operations_total", &[("operation", "add"), ok])
+        );
+        assert_eq!(
+            2,
+            metrics
+                .counter("repo_operations_total", &[("operation", "get"), ok])
+        );
+        assert_eq!(4, metrics.counter("repo_operations_total", &[ok]));
+        assert_eq!(0, metrics.counter("repo_errors_total", &[]));
+        assert_eq!(
+            2,
+            metrics.samples(
+                "repo_operation_duration_seconds",
+                &[("operation", "get")]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_test() {
+        let capture = MetricsCapture::new();
+        let _guard = capture.install();
+        let repo = MeteredRepo::new(MemRepo::<TestItem>::new());
+        let item = TestItem::new(1, "one");
+
+        assert!(repo.get(&Key::new(2)).await.is_err());
+        assert!(repo.update(item.clone()).await.is_err());
+
+        repo.inner().fail_next(1);
+
+        assert!(repo.add(item).await.is_err());
+
+        let metrics = capture.snapshot();
+
+        assert_eq!(
+            3,
+            metrics.counter("repo_operations_total", &[("outcome", "error")])
+        );
+        assert_eq!(
+            2,
+            metrics.counter("repo_errors_total", &[("error", "NotFound")])
+        );
+        assert_eq!(
+            1,
+            metrics.counter(
+                "repo_errors_total",
+                &[("operation", "add"), ("error", "Io")]
+            )
+        );
+    }
+}
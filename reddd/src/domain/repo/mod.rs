@@ -0,0 +1,519 @@
+use chrono::{DateTime, Utc};
+
+use super::{Entity, MutableEntity, SoftDeletableEntity};
+
+mod cached;
+mod memory;
+mod validating;
+
+#[cfg(feature = "tracing")]
+mod instrumented;
+#[cfg(feature = "metrics")]
+mod metered;
+#[cfg(feature = "retry")]
+mod retry;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub use cached::*;
+#[cfg(feature = "tracing")]
+pub use instrumented::*;
+pub use memory::*;
+#[cfg(feature = "metrics")]
+pub use metered::*;
+#[cfg(feature = "retry")]
+pub use retry::*;
+pub use validating::*;
+
+/// A struct that holds repository pagination info.
+///
+/// # Example
+///
+/// ```ignore
+/// let my_user = users_repo.get(&user_id).await?;
+///
+/// // get 10 items before `my_user`
+/// let prev_page = users_repo.get_page(Pagiation {
+///     before_key: my_user.id(),
+///     before_timestamp: my_user.created_at(),
+///     page_size: 10,
+/// }).await?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Pagination<E: Entity> {
+    /// The key which to get items before.
+    ///
+    /// This field is needed to eliminate possible duplications that can
+    /// occure when paginating using timestamp only, as multiple records
+    /// can have the same timestamp.
+    pub before_key: E::Key,
+
+    /// The creation timestamp which to get items before.
+    ///
+    /// This is usually obtained using [created_at](Entity::created_at())
+    /// method of an entity.
+    pub before_timestamp: DateTime<Utc>,
+
+    /// The number of items to include in a page.
+    pub page_size: usize,
+}
+
+/// The outcome of an [`upsert`](WriteRepo::upsert) operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Upserted<E> {
+    /// The item did not exist, and was added to the data repository.
+    Inserted(E),
+
+    /// The item already existed, and was updated in the data repository.
+    Updated(E),
+}
+
+impl<E> Upserted<E> {
+    /// Checks whether the item was added to the data repository.
+    pub fn is_inserted(&self) -> bool {
+        matches!(self, Self::Inserted(_))
+    }
+
+    /// Checks whether an already existing item was updated.
+    pub fn is_updated(&self) -> bool {
+        matches!(self, Self::Updated(_))
+    }
+
+    /// Moves the written item out of `self`, regardless of the outcome.
+    pub fn into_inner(self) -> E {
+        match self {
+            | Self::Inserted(item) | Self::Updated(item) => item,
+        }
+    }
+}
+
+/// A condition that a stored item must satisfy for a conditional operation
+/// (e.g. [`update_if`](WriteRepo::update_if)) to proceed.
+pub type Predicate<'a, E> = dyn Fn(&E) -> bool + Send + Sync + 'a;
+
+/// An enumeration of ways soft-deleted items can be treated with when reading
+/// from a [`SoftDeleteRepo`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeletedItems {
+    /// Soft-deleted items are treated as if they do not exist.
+    #[default]
+    Exclude,
+
+    /// Soft-deleted items are treated like any other item.
+    Include,
+
+    /// Only soft-deleted items are considered.
+    Only,
+}
+
+/// A trait to be implemented by data repositories.
+///
+/// This trait provides read-only methods that have no side effects on the
+/// stored data.
+///
+/// When [`Self::Entity`](ReadRepo::Entity) is a [`SoftDeletableEntity`],
+/// implementations of this trait **shall** treat soft-deleted items as if they
+/// do not exist (i.e. [`get`](ReadRepo::get) returns
+/// [`RepoError::NotFound`](error::RepoError::NotFound) for them). Use
+/// [`SoftDeleteRepo`] to read them.
+#[async_trait::async_trait]
+pub trait ReadRepo: Sync {
+    /// The type of the entity that this repository operates on.
+    type Entity: Entity + Send + Sync;
+
+    /// Gets a single item by its identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The identifier of the item to get.
+    async fn get(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> error::RepoResult<Self::Entity>;
+
+    /// Gets a page of items using passed pagination parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Pagination parameters to get the page with.
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> error::RepoResult<Vec<Self::Entity>>;
+
+    /// Checks whether an item with the passed identifier exists or not.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The identifier of the item to check.
+    async fn exists(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> error::RepoResult<bool>;
+}
+
+/// A trait to add mutability operations to repositories.
+///
+/// Unlike [`Repo`] trait, implementations of this trait can have side effects
+/// on the stored data, thus extra care needs to be taken when used.
+#[async_trait::async_trait]
+pub trait WriteRepo: Sync {
+    /// The type of the entity that this repository operates on.
+    type Entity: MutableEntity + Send + Sync;
+
+    /// Adds an item to the data repository.
+    ///
+    /// If an item with the same key already exists,
+    /// [`RepoError::DuplicateValue`](error::RepoError::DuplicateValue) is
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to be added
+    async fn add(&self, item: Self::Entity) -> error::RepoResult<Self::Entity>;
+
+    /// Updates an item in the data repository.
+    ///
+    /// The item **must** be present in the data repository, as implementations
+    /// of this trait **shall** not create the item if it does not exist, and
+    /// return [`RepoError::NotFound`](error::RepoError::NotFound) instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to be updated
+    async fn update(
+        &self,
+        item: Self::Entity,
+    ) -> error::RepoResult<Self::Entity>;
+
+    /// Adds an item to the data repository if it does not exist, or updates
+    /// it otherwise.
+    ///
+    /// Unlike calling [`add`](WriteRepo::add) or [`update`](WriteRepo::update)
+    /// based on the result of [`exists`](ReadRepo::exists), implementations
+    /// of this trait **shall** check for the item existence and write it
+    /// atomically.
+    ///
+    /// The default implementation returns
+    /// [`RepoError::Unsupported`](error::RepoError::Unsupported), as upserts
+    /// cannot be made atomic on top of the other methods of this trait.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to be added or updated
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> error::RepoResult<Upserted<Self::Entity>> {
+        let _ = item;
+
+        Err(error::RepoError::Unsupported("upsert".into()))
+    }
+
+    /// Updates an item in the data repository only if its currently stored
+    /// version satisfies `predicate`.
+    ///
+    /// Implementations of this trait **shall** evaluate the predicate and
+    /// write the item atomically, which allows compare-and-swap semantics
+    /// (e.g. by comparing [`updated_at`](MutableEntity::updated_at) values).
+    ///
+    /// If the item does not exist,
+    /// [`RepoError::NotFound`](error::RepoError::NotFound) is returned, and if
+    /// the stored item does not satisfy `predicate`,
+    /// [`RepoError::PreconditionFailed`](error::RepoError::PreconditionFailed)
+    /// is returned, leaving the stored item untouched.
+    ///
+    /// The default implementation returns
+    /// [`RepoError::Unsupported`](error::RepoError::Unsupported), as the
+    /// stored item cannot be read through this trait.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut user = users_repo.get(&user_id).await?;
+    /// let last_seen = *user.updated_at();
+    ///
+    /// user.touch();
+    ///
+    /// // fails if `user` was updated by someone else in the meantime
+    /// let user = users_repo
+    ///     .update_if(user, &|stored| stored.updated_at() == &last_seen)
+    ///     .await?;
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to be updated
+    /// * `predicate` - Condition the stored item must satisfy
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> error::RepoResult<Self::Entity> {
+        let _ = (item, predicate);
+
+        Err(error::RepoError::Unsupported("update_if".into()))
+    }
+
+    /// Removes an item from the data repository.
+    ///
+    /// This is always a hard delete, even for [`SoftDeletableEntity`] items.
+    /// Use [`SoftDeleteRepo::soft_remove`] to keep a tombstone instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item to be removed
+    async fn remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> error::RepoResult<()>;
+}
+
+/// A trait to add soft deletion operations to repositories.
+///
+/// Soft-deleted items are kept in the data repository, but are hidden from
+/// [`ReadRepo`] operations.
+#[async_trait::async_trait]
+pub trait SoftDeleteRepo: Sync {
+    /// The type of the entity that this repository operates on.
+    type Entity: SoftDeletableEntity + Send + Sync;
+
+    /// Gets a single item by its identifier, treating soft-deleted items as
+    /// specified by `deleted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The identifier of the item to get.
+    /// * `deleted` - How soft-deleted items are treated.
+    async fn get_with(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+        deleted: DeletedItems,
+    ) -> error::RepoResult<Self::Entity>;
+
+    /// Gets a page of items using passed pagination parameters, treating
+    /// soft-deleted items as specified by `deleted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Pagination parameters to get the page with.
+    /// * `deleted` - How soft-deleted items are treated.
+    async fn get_page_with(
+        &self,
+        params: Pagination<Self::Entity>,
+        deleted: DeletedItems,
+    ) -> error::RepoResult<Vec<Self::Entity>>;
+
+    /// Marks an item as deleted without removing it from the data repository.
+    ///
+    /// If the item does not exist or is already soft-deleted,
+    /// [`RepoError::NotFound`](error::RepoError::NotFound) is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item to be soft-deleted
+    async fn soft_remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> error::RepoResult<()>;
+
+    /// Restores a soft-deleted item.
+    ///
+    /// If the item does not exist or is not soft-deleted,
+    /// [`RepoError::NotFound`](error::RepoError::NotFound) is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item to be restored
+    async fn restore(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> error::RepoResult<Self::Entity>;
+}
+
+/// A trait to be implemented by data repositories.
+pub trait Repo: ReadRepo + WriteRepo {}
+
+pub mod error {
+    use std::error::Error;
+
+    use thiserror::Error;
+
+    use crate::domain::InvariantViolation;
+
+    /// A type definition that simplifies [`Result<T, E>`] usage when dealing
+    /// with data repositories.
+    pub type RepoResult<T> = Result<T, RepoError>;
+
+    /// An enumeration of possible errors that can occure during a repository
+    /// operation execution.
+    #[derive(Debug, Error)]
+    pub enum RepoError {
+        /// Internal Input/Output error.
+        #[error("i/o error: {0}")]
+        Io(std::io::Error),
+
+        /// An item that was referred to does not exist.
+        #[error("not found: {0}")]
+        NotFound(String),
+
+        /// An item with similar data to the item being added/updated already
+        /// exists.
+        #[error("duplicate item: {0}")]
+        DuplicateValue(String),
+
+        /// The stored item did not satisfy the condition of a conditional
+        /// operation.
+        #[error("precondition failed: {0}")]
+        PreconditionFailed(String),
+
+        /// The operation is not supported by the data repository.
+        #[error("unsupported operation: {0}")]
+        Unsupported(String),
+
+        /// Invalid data was provided.
+        #[error("invlalid parameter: {0}")]
+        InvalidParameter(String),
+
+        /// The item being added/updated violates one of its invariants.
+        #[error(transparent)]
+        InvariantViolated(#[from] InvariantViolation),
+
+        /// Other error types. This can hold user-defined types, as well as,
+        /// any other error type.
+        #[error(transparent)]
+        Other(Box<dyn Error + Send + Sync>),
+    }
+
+    impl RepoError {
+        /// Checks whether the error is likely to be transient, so that the
+        /// failed operation can be retried.
+        ///
+        /// Only [`RepoError::Io`] errors are considered transient, as the
+        /// rest of the variants are caused by the data or by the operation
+        /// itself, which would fail again if retried.
+        pub fn is_retryable(&self) -> bool {
+            matches!(self, Self::Io(_))
+        }
+
+        /// Gets the name of the error variant, which is useful to label
+        /// errors with in logs and metrics.
+        pub fn kind(&self) -> &'static str {
+            match self {
+                | Self::Io(_) => "Io",
+                | Self::NotFound(_) => "NotFound",
+                | Self::DuplicateValue(_) => "DuplicateValue",
+                | Self::PreconditionFailed(_) => "PreconditionFailed",
+                | Self::Unsupported(_) => "Unsupported",
+                | Self::InvalidParameter(_) => "InvalidParameter",
+                | Self::InvariantViolated(_) => "InvariantViolated",
+                | Self::Other(_) => "Other",
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        error::{RepoError, RepoResult},
+        testing::{MemRepo, TestItem},
+        *,
+    };
+    use crate::domain::Key;
+
+    /// A repository that only implements the required methods.
+    struct MinimalRepo;
+
+    #[async_trait::async_trait]
+    impl WriteRepo for MinimalRepo {
+        type Entity = TestItem;
+
+        async fn add(&self, item: TestItem) -> RepoResult<TestItem> {
+            Ok(item)
+        }
+
+        async fn update(&self, item: TestItem) -> RepoResult<TestItem> {
+            Ok(item)
+        }
+
+        async fn remove(&self, _: &Key<TestItem, u32>) -> RepoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn upsert_test() {
+        let repo = MemRepo::new();
+        let mut item = TestItem::new(1, "one");
+
+        let upserted = repo.upsert(item.clone()).await.unwrap();
+
+        assert!(upserted.is_inserted());
+        assert_eq!(Upserted::Inserted(item.clone()), upserted);
+
+        item.value = "updated".into();
+
+        let upserted = repo.upsert(item.clone()).await.unwrap();
+
+        assert!(upserted.is_updated());
+        assert_eq!(item, upserted.into_inner());
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn update_if_test() {
+        let repo = MemRepo::new();
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+        let mut updated = item.clone();
+
+        updated.value = "updated".into();
+
+        assert!(matches!(
+            repo.update_if(updated.clone(), &|s| s.value == "other")
+                .await,
+            Err(RepoError::PreconditionFailed(_))
+        ));
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+
+        repo.update_if(updated.clone(), &|s| s.value == "one")
+            .await
+            .unwrap();
+
+        assert_eq!(updated, repo.get(item.id()).await.unwrap());
+        assert!(matches!(
+            repo.update_if(TestItem::new(2, "two"), &|_| true).await,
+            Err(RepoError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn default_methods_test() {
+        let item = TestItem::new(1, "one");
+
+        assert!(matches!(
+            MinimalRepo.upsert(item.clone()).await,
+            Err(RepoError::Unsupported(_))
+        ));
+        assert!(matches!(
+            MinimalRepo.update_if(item, &|_| true).await,
+            Err(RepoError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn object_safety_test() {
+        let repo: Box<dyn WriteRepo<Entity = TestItem>> =
+            Box::new(MemRepo::new());
+        let item = repo.add(TestItem::new(1, "one")).await.unwrap();
+        let value = item.value.clone();
+
+        assert_eq!(
+            item,
+            repo.update_if(item.clone(), &|s| s.value == value)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::{future::Future, time::Duration};
+
+use super::{
+    error::{RepoError, RepoResult},
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::Entity;
+
+/// An enumeration of strategies to compute delays between retry attempts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    /// Retry immediately.
+    None,
+
+    /// Wait the same duration before each retry.
+    Fixed(Duration),
+
+    /// Wait `initial` before the first retry, and double the delay before
+    /// each subsequent one, up to `max`.
+    Exponential {
+        /// The delay before the first retry.
+        initial: Duration,
+
+        /// The maximum delay between two attempts.
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    /// Gets the delay to wait after a failed attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The 1-based number of the attempt that failed.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match *self {
+            | Backoff::None => Duration::ZERO,
+            | Backoff::Fixed(delay) => delay,
+            | Backoff::Exponential { initial, max } => initial
+                .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .map_or(max, |delay| delay.min(max)),
+        }
+    }
+}
+
+/// An enumeration of [`WriteRepo`] operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WriteOp {
+    /// [`WriteRepo::add`]
+    Add,
+
+    /// [`WriteRepo::update`]
+    Update,
+
+    /// [`WriteRepo::upsert`]
+    Upsert,
+
+    /// [`WriteRepo::update_if`]
+    UpdateIf,
+
+    /// [`WriteRepo::remove`]
+    Remove,
+}
+
+/// A struct that holds [`RetryingRepo`] configuration.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts of a single operation, including the
+    /// first one.
+    pub max_attempts: u32,
+
+    /// The strategy of delays between attempts.
+    pub backoff: Backoff,
+
+    /// The maximum duration of a single attempt, if any.
+    ///
+    /// Timed out attempts fail with a [`RepoError::Io`] error of kind
+    /// [`std::io::ErrorKind::TimedOut`].
+    pub timeout: Option<Duration>,
+
+    /// A function that classifies which errors are worth retrying.
+    pub is_retryable: fn(&RepoError) -> bool,
+
+    /// Write operations that are safe to be retried.
+    ///
+    /// A write operation that failed (e.g. timed out) could still have been
+    /// applied, thus write operations are only retried when they are known
+    /// to be idempotent for the inner repository.
+    pub idempotent_writes: Vec<WriteOp>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Backoff::Exponential {
+                initial: Duration::from_millis(100),
+                max: Duration::from_secs(5),
+            },
+            timeout: None,
+            is_retryable: RepoError::is_retryable,
+            idempotent_writes: Vec::new(),
+        }
+    }
+}
+
+/// A repository decorator that retries failed operations of an inner
+/// repository according to a [`RetryPolicy`].
+///
+/// Read operations are always retried, while write operations are only
+/// retried when listed in [`RetryPolicy::idempotent_writes`]. Timeouts and
+/// delays are driven by the [`tokio`] runtime.
+///
+/// # Example
+///
+/// ```ignore
+/// let users_repo = RetryingRepo::new(
+///     users_repo,
+///     RetryPolicy {
+///         timeout: Some(Duration::from_secs(1)),
+///         idempotent_writes: vec![WriteOp::Update, WriteOp::Remove],
+///         ..Default::default()
+///     },
+/// );
+/// ```
+pub struct RetryingRepo<R> {
+    inner: R,
+    policy: RetryPolicy,
+}
+
+impl<R> RetryingRepo<R> {
+    /// Creates a new retrying repository on top of `inner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The repository to retry operations of.
+    /// * `policy` - The retry policy.
+    pub fn new(inner: R, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Gets a reference to the inner repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a reference to the retry policy.
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Runs an operation, retrying it if `retry` is set.
+    async fn run<T, F, Fut>(&self, retry: bool, mut op: F) -> RepoResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RepoResult<T>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let result = match self.policy.timeout {
+                | Some(timeout) => tokio::time::timeout(timeout, op())
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(RepoError::Io(std::io::ErrorKind::TimedOut.into()))
+                    }),
+                | None => op().await,
+            };
+
+            match result {
+                | Err(err)
+                    if retry
+                        && attempt < self.policy.max_attempts
+                        && (self.policy.is_retryable)(&err) =>
+                {
+                    tokio::time::sleep(self.policy.backoff.delay(attempt))
+                        .await;
+                }
+                | result => return result,
+            }
+        }
+    }
+
+    /// Runs a write operation, retrying it only if it is idempotent.
+    async fn run_write<T, F, Fut>(&self, op: WriteOp, f: F) -> RepoResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RepoResult<T>>,
+    {
+        self.run(self.policy.idempotent_writes.contains(&op), f)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> ReadRepo for RetryingRepo<R>
+where
+    R: ReadRepo,
+    <R::Entity as Entity>::Key: Clone + Send + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn get(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<Self::Entity> {
+        self.run(true, || self.inner.get(key)).await
+    }
+
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> RepoResult<Vec<Self::Entity>> {
+        self.run(true, || {
+            self.inner.get_page(Pagination {
+                before_key: params.before_key.clone(),
+                ..params
+            })
+        })
+        .await
+    }
+
+    async fn exists(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<bool> {
+        self.run(true, || self.inner.exists(key)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> WriteRepo for RetryingRepo<R>
+where
+    R: WriteRepo,
+    R::Entity: Clone,
+    <R::Entity as Entity>::Key: Sync,
+{
+    type Entity = R::Entity;
+
+    async fn add(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        self.run_write(WriteOp::Add, || self.inner.add(item.clone()))
+            .await
+    }
+
+    async fn update(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        self.run_write(WriteOp::Update, || self.inner.update(item.clone()))
+            .await
+    }
+
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> RepoResult<Upserted<Self::Entity>> {
+        self.run_write(WriteOp::Upsert, || self.inner.upsert(item.clone()))
+            .await
+    }
+
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> RepoResult<Self::Entity> {
+        self.run_write(WriteOp::UpdateIf, || {
+            self.inner.update_if(item.clone(), predicate)
+        })
+        .await
+    }
+
+    async fn remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<()> {
+        self.run_write(WriteOp::Remove, || self.inner.remove(key))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repo::testing::{MemRepo, TestItem};
+
+    fn retrying(policy: RetryPolicy) -> RetryingRepo<MemRepo<TestItem>> {
+        RetryingRepo::new(
+            MemRepo::new(),
+            RetryPolicy {
+                backoff: Backoff::None,
+                ..policy
+            },
+        )
+    }
+
+    #[test]
+    fn backoff_test() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+        };
+
+        assert_eq!(Duration::ZERO, Backoff::None.delay(3));
+        assert_eq!(
+            Duration::from_secs(2),
+            Backoff::Fixed(Duration::from_secs(2)).delay(5)
+        );
+        assert_eq!(Duration::from_millis(100), backoff.delay(1));
+        assert_eq!(Duration::from_millis(200), backoff.delay(2));
+        assert_eq!(Duration::from_millis(800), backoff.delay(4));
+        assert_eq!(Duration::from_secs(1), backoff.delay(5));
+        assert_eq!(Duration::from_secs(1), backoff.delay(u32::MAX));
+    }
+
+    #[test]
+    fn retryable_errors_test() {
+        assert!(RepoError::Io(std::io::ErrorKind::Other.into()).is_retryable());
+        assert!(!RepoError::NotFound(String::new()).is_retryable());
+        assert!(!RepoError::DuplicateValue(String::new()).is_retryable());
+        assert!(!RepoError::PreconditionFailed(String::new()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn read_retry_test() {
+        let repo = retrying(Default::default());
+        let item = TestItem::new(1, "one");
+
+        repo.inner().add(item.clone()).await.unwrap();
+        repo.inner().fail_next(2);
+
+        assert_eq!(item, repo.get(item.id()).await.unwrap());
+        assert_eq!(4, repo.inner().calls());
+
+        repo.inner().fail_next(3);
+
+        assert!(matches!(
+            repo.exists(item.id()).await,
+            Err(RepoError::Io(_))
+        ));
+        assert_eq!(7, repo.inner().calls());
+    }
+
+    #[tokio::test]
+    async fn non_retryable_error_test() {
+        let repo = retrying(RetryPolicy {
+            idempotent_writes: vec![WriteOp::Add],
+            ..Default::default()
+        });
+        let item = TestItem::new(1, "one");
+
+        repo.add(item.clone()).await.unwrap();
+
+        assert!(matches!(
+            repo.add(item).await,
+            Err(RepoError::DuplicateValue(_))
+        ));
+        assert_eq!(2, repo.inner().calls());
+    }
+
+    #[tokio::test]
+    async fn idempotent_writes_test() {
+        let repo = retrying(RetryPolicy {
+            idempotent_writes: vec![WriteOp::Update],
+            ..Default::default()
+        });
+        let mut item = TestItem::new(1, "one");
+
+        repo.inner().fail_next(1);
+
+        assert!(repo.add(item.clone()).await.is_err());
+        assert_eq!(1, repo.inner().calls());
+
+        repo.add(item.clone()).await.unwrap();
+        item.value = "updated".into();
+        repo.inner().fail_next(1);
+
+        assert_eq!(item, repo.update(item.clone()).await.unwrap());
+        assert_eq!(4, repo.inner().calls());
+    }
+
+    #[tokio::test]
+    async fn timeout_test() {
+        let repo = retrying(RetryPolicy {
+            max_attempts: 2,
+            timeout: Some(Duration::from_millis(10)),
+            ..Default::default()
+        });
+        let item = TestItem::new(1, "one");
+
+        repo.inner().set_latency(Duration::from_secs(1));
+
+        match repo.get(item.id()).await {
+            | Err(RepoError::Io(err)) => {
+                assert_eq!(std::io::ErrorKind::TimedOut, err.kind())
+            }
+            | _ => panic!("expected a timeout error"),
+        }
+
+        assert_eq!(2, repo.inner().calls());
+    }
+}
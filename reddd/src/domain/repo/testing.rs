@@ -0,0 +1,326 @@
+//! Test doubles shared by repository decorator tests, some of which are only
+//! compiled with specific features enabled.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+        MutexGuard,
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use reddd_macros::MutableEntity;
+
+use super::{
+    error::{RepoError, RepoResult},
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::{Entity, Key, MutableEntity};
+
+/// A sample entity to test repositories with.
+#[derive(Clone, Debug, PartialEq, MutableEntity)]
+pub(crate) struct TestItem {
+    pub(crate) id: Key<TestItem, u32>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) updated_at: DateTime<Utc>,
+    pub(crate) value: String,
+}
+
+impl TestItem {
+    pub(crate) fn new(id: u32, value: &str) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Key::new(id),
+            created_at: now,
+            updated_at: now,
+            value: value.into(),
+        }
+    }
+}
+
+/// A simple in-memory repository that counts calls made to it, and can be
+/// made to fail or to respond slowly.
+pub(crate) struct MemRepo<E: Entity> {
+    items: Mutex<HashMap<E::Key, E>>,
+    calls: AtomicUsize,
+    failures: AtomicUsize,
+    latency: Mutex<Duration>,
+}
+
+impl<E: Entity> MemRepo<E> {
+    pub(crate) fn new() -> Self {
+        Self {
+            items: Default::default(),
+            calls: Default::default(),
+            failures: Default::default(),
+            latency: Default::default(),
+        }
+    }
+
+    /// Gets the number of calls made to the repository so far.
+    pub(crate) fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Makes the next `count` calls fail with an i/o error.
+    #[cfg(any(feature = "metrics", feature = "retry"))]
+    pub(crate) fn fail_next(&self, count: usize) {
+        self.failures.store(count, Ordering::SeqCst);
+    }
+
+    /// Makes all subsequent calls take at least `latency` to respond.
+    #[cfg(feature = "retry")]
+    pub(crate) fn set_latency(&self, latency: Duration) {
+        *self.latency.lock().unwrap() = latency;
+    }
+
+    async fn call(&self) -> RepoResult<MutexGuard<'_, HashMap<E::Key, E>>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+
+        let latency = *self.latency.lock().unwrap();
+
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        let failed = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| {
+                f.checked_sub(1)
+            })
+            .is_ok();
+
+        if failed {
+            return Err(RepoError::Io(std::io::ErrorKind::Other.into()));
+        }
+
+        Ok(self.items.lock().unwrap())
+    }
+}
+
+fn not_found() -> RepoError {
+    RepoError::NotFound("item does not exist".into())
+}
+
+#[async_trait::async_trait]
+impl<E> ReadRepo for MemRepo<E>
+where
+    E: Entity + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + PartialOrd + Send + Sync,
+{
+    type Entity = E;
+
+    async fn get(&self, key: &E::Key) -> RepoResult<E> {
+        self.call().await?.get(key).cloned().ok_or_else(not_found)
+    }
+
+    async fn get_page(&self, params: Pagination<E>) -> RepoResult<Vec<E>> {
+        let mut items: Vec<_> = self
+            .call()
+            .await?
+            .values()
+            .filter(|i| {
+                (i.created_at(), i.id())
+                    < (&params.before_timestamp, &params.before_key)
+            })
+            .cloned()
+            .collect();
+
+        items.sort_by(|a, b| {
+            (b.created_at(), b.id())
+                .partial_cmp(&(a.created_at(), a.id()))
+                .unwrap()
+        });
+        items.truncate(params.page_size);
+
+        Ok(items)
+    }
+
+    async fn exists(&self, key: &E::Key) -> RepoResult<bool> {
+        Ok(self.call().await?.contains_key(key))
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> WriteRepo for MemRepo<E>
+where
+    E: MutableEntity + Clone + Send + Sync,
+    E::Key: Clone + Eq + Hash + Send + Sync,
+{
+    type Entity = E;
+
+    async fn add(&self, item: E) -> RepoResult<E> {
+        let mut items = self.call().await?;
+
+        if items.contains_key(item.id()) {
+            return Err(RepoError::DuplicateValue("item exists".into()));
+        }
+
+        items.insert(item.id().clone(), item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(&self, item: E) -> RepoResult<E> {
+        let mut items = self.call().await?;
+        let stored = items.get_mut(item.id()).ok_or_else(not_found)?;
+
+        *stored = item.clone();
+
+        Ok(item)
+    }
+
+    async fn upsert(&self, item: E) -> RepoResult<Upserted<E>> {
+        match self.call().await?.insert(item.id().clone(), item.clone()) {
+            | Some(_) => Ok(Upserted::Updated(item)),
+            | None => Ok(Upserted::Inserted(item)),
+        }
+    }
+
+    async fn update_if(
+        &self,
+        item: E,
+        predicate: &Predicate<'_, E>,
+    ) -> RepoResult<E> {
+        let mut items = self.call().await?;
+        let stored = items.get_mut(item.id()).ok_or_else(not_found)?;
+
+        if !predicate(stored) {
+            return Err(RepoError::PreconditionFailed("predicate".into()));
+        }
+
+        *stored = item.clone();
+
+        Ok(item)
+    }
+
+    async fn remove(&self, key: &E::Key) -> RepoResult<()> {
+        self.call()
+            .await?
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(not_found)
+    }
+}
+
+/// A writer that captures closed spans formatted by a [`tracing`] subscriber.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+pub(crate) struct SpanCapture(std::sync::Arc<Mutex<Vec<u8>>>);
+
+#[cfg(feature = "tracing")]
+impl SpanCapture {
+    /// Sets a subscriber writing to `self` as the default of this thread.
+    pub(crate) fn install(&self) -> tracing::subscriber::DefaultGuard {
+        let writer = self.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .with_ansi(false)
+            .finish();
+
+        tracing::subscriber::set_default(subscriber)
+    }
+
+    /// Gets the captured output so far.
+    pub(crate) fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl std::io::Write for SpanCapture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A recorder that captures metrics emitted through the [`metrics`] facade.
+#[cfg(feature = "metrics")]
+pub(crate) struct MetricsCapture(metrics_util::debugging::DebuggingRecorder);
+
+#[cfg(feature = "metrics")]
+impl MetricsCapture {
+    pub(crate) fn new() -> Self {
+        Self(metrics_util::debugging::DebuggingRecorder::new())
+    }
+
+    /// Sets `self` as the default recorder of this thread.
+    pub(crate) fn install(&self) -> metrics::LocalRecorderGuard<'_> {
+        metrics::set_default_local_recorder(&self.0)
+    }
+
+    /// Gets all metrics captured so far.
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot(
+            self.0
+                .snapshotter()
+                .snapshot()
+                .into_vec()
+                .into_iter()
+                .map(|(key, _, _, value)| (key.key().clone(), value))
+                .collect(),
+        )
+    }
+}
+
+/// A point-in-time snapshot of captured metrics.
+#[cfg(feature = "metrics")]
+pub(crate) struct MetricsSnapshot(
+    Vec<(metrics::Key, metrics_util::debugging::DebugValue)>,
+);
+
+#[cfg(feature = "metrics")]
+impl MetricsSnapshot {
+    /// Gets the value of the counter named `name` that has all of `labels`.
+    pub(crate) fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.find(name, labels)
+            .map(|value| match value {
+                | metrics_util::debugging::DebugValue::Counter(c) => *c,
+                | _ => panic!("`{name}` is not a counter"),
+            })
+            .sum()
+    }
+
+    /// Gets the number of samples of the histogram named `name` that has all
+    /// of `labels`.
+    pub(crate) fn samples(&self, name: &str, labels: &[(&str, &str)]) -> usize {
+        self.find(name, labels)
+            .map(|value| match value {
+                | metrics_util::debugging::DebugValue::Histogram(h) => h.len(),
+                | _ => panic!("`{name}` is not a histogram"),
+            })
+            .sum()
+    }
+
+    fn find<'a>(
+        &'a self,
+        name: &'a str,
+        labels: &'a [(&str, &str)],
+    ) -> impl Iterator<Item = &'a metrics_util::debugging::DebugValue> {
+        self.0
+            .iter()
+            .filter(move |(key, _)| {
+                key.name() == name
+                    && labels.iter().all(|(k, v)| {
+                        key.labels().any(|l| l.key() == *k && l.value() == *v)
+                    })
+            })
+            .map(|(_, value)| value)
+    }
+}
@@ -0,0 +1,233 @@
+use super::{
+    error::RepoResult,
+    Pagination,
+    Predicate,
+    ReadRepo,
+    Upserted,
+    WriteRepo,
+};
+use crate::domain::Entity;
+
+/// A repository decorator that checks the
+/// [invariants](Entity::check_invariants) of items before they are written
+/// to an inner repository.
+///
+/// Items that violate any of their invariants are rejected with
+/// [`RepoError::InvariantViolated`](super::error::RepoError::InvariantViolated),
+/// without reaching the inner repository.
+///
+/// # Example
+///
+/// ```ignore
+/// let bookings_repo = ValidatingRepo::new(bookings_repo);
+///
+/// booking.end_date = booking.start_date - Days::new(1);
+///
+/// // fails with `RepoError::InvariantViolated`
+/// bookings_repo.update(booking).await?;
+/// ```
+pub struct ValidatingRepo<R> {
+    inner: R,
+}
+
+impl<R> ValidatingRepo<R> {
+    /// Creates a new validating repository on top of `inner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The repository to write valid items to.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Gets a reference to the inner repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> ReadRepo for ValidatingRepo<R>
+where
+    R: ReadRepo,
+    <R::Entity as Entity>::Key: Send + Sync,
+{
+    type Entity = R::Entity;
+
+    async fn get(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<Self::Entity> {
+        self.inner.get(key).await
+    }
+
+    async fn get_page(
+        &self,
+        params: Pagination<Self::Entity>,
+    ) -> RepoResult<Vec<Self::Entity>> {
+        self.inner.get_page(params).await
+    }
+
+    async fn exists(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<bool> {
+        self.inner.exists(key).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> WriteRepo for ValidatingRepo<R>
+where
+    R: WriteRepo,
+    <R::Entity as Entity>::Key: Sync,
+{
+    type Entity = R::Entity;
+
+    async fn add(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        item.check_invariants()?;
+
+        self.inner.add(item).await
+    }
+
+    async fn update(&self, item: Self::Entity) -> RepoResult<Self::Entity> {
+        item.check_invariants()?;
+
+        self.inner.update(item).await
+    }
+
+    async fn upsert(
+        &self,
+        item: Self::Entity,
+    ) -> RepoResult<Upserted<Self::Entity>> {
+        item.check_invariants()?;
+
+        self.inner.upsert(item).await
+    }
+
+    async fn update_if(
+        &self,
+        item: Self::Entity,
+        predicate: &Predicate<'_, Self::Entity>,
+    ) -> RepoResult<Self::Entity> {
+        item.check_invariants()?;
+
+        self.inner.update_if(item, predicate).await
+    }
+
+    async fn remove(
+        &self,
+        key: &<Self::Entity as Entity>::Key,
+    ) -> RepoResult<()> {
+        self.inner.remove(key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use reddd_macros::MutableEntity;
+
+    use super::*;
+    use crate::domain::{
+        error::RepoError,
+        repo::testing::MemRepo,
+        InvariantViolation,
+        Key,
+        MutableEntity,
+    };
+
+    #[derive(Clone, Debug, MutableEntity)]
+    #[entity(invariant = ends_after_start, invariant = checks::has_guests)]
+    struct Booking {
+        id: Key<Booking, u32>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        guests: u8,
+    }
+
+    fn ends_after_start(booking: &Booking) -> Result<(), &'static str> {
+        match booking.end > booking.start {
+            | true => Ok(()),
+            | false => Err("end must be after start"),
+        }
+    }
+
+    mod checks {
+        pub(super) fn has_guests(
+            booking: &super::Booking,
+        ) -> Result<(), String> {
+            match booking.guests {
+                | 0 => Err("no guests".to_string()),
+                | _ => Ok(()),
+            }
+        }
+    }
+
+    fn booking() -> Booking {
+        let now = Utc::now();
+
+        Booking {
+            id: Key::new(1),
+            created_at: now,
+            updated_at: now,
+            start: now,
+            end: now + Duration::days(1),
+            guests: 2,
+        }
+    }
+
+    #[test]
+    fn check_invariants_test() {
+        let mut booking = booking().validated().unwrap();
+        let updated_at = *booking.updated_at();
+
+        booking.guests = 0;
+
+        assert_eq!(
+            Err(InvariantViolation::new(
+                "Booking",
+                "checks::has_guests",
+                "no guests"
+            )),
+            booking.try_touch().map(|_| ())
+        );
+        assert_eq!(&updated_at, booking.updated_at());
+
+        booking.end = booking.start;
+
+        let violation = booking.check_invariants().unwrap_err();
+
+        assert_eq!("ends_after_start", violation.invariant);
+        assert_eq!(
+            "invariant `ends_after_start` of `Booking` violated: end must be \
+             after start",
+            violation.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn write_test() {
+        let repo = ValidatingRepo::new(MemRepo::<Booking>::new());
+        let mut item = repo.add(booking()).await.unwrap();
+
+        item.guests = 0;
+
+        assert!(matches!(
+            repo.update(item.clone()).await,
+            Err(RepoError::InvariantViolated(_))
+        ));
+        assert!(matches!(
+            repo.upsert(item.clone()).await,
+            Err(RepoError::InvariantViolated(_))
+        ));
+        assert!(matches!(
+            repo.update_if(item, &|_| true).await,
+            Err(RepoError::InvariantViolated(_))
+        ));
+        assert_eq!(2, repo.get(&Key::new(1)).await.unwrap().guests);
+        assert_eq!(2, repo.inner().calls());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+
+pub use zeroize::Zeroize;
+
+/// The placeholder that sensitive values are replaced with.
+const REDACTED: &str = "[REDACTED]";
+
+/// A struct that holds a sensitive value (e.g. passwords and access tokens),
+/// so it is never leaked implicitly (e.g. to logs).
+///
+/// Secrets are redacted when debugged or displayed, and their value is
+/// zeroized when dropped, so it can only be read with
+/// [`Secret::expose_secret`]. Secrets can be deserialized, but do not
+/// implement `Serialize`, so they are never written out by accident (nor
+/// replaced by a placeholder that would be read back as the secret). Fields
+/// that must be serialized in the clear (e.g. to a credentials store) opt in
+/// with `#[serde(serialize_with = "Secret::serialize_exposed")]`, while ones
+/// that must not be serialized at all use `#[serde(skip_serializing)]`.
+///
+/// Value types that hold sensitive values themselves can be derived with
+/// `#[value_type(sensitive)]` instead (see [`ValueType`](super::ValueType)).
+///
+/// # Generic Arguments
+///
+/// * `V` - Type of the wrapped sensitive value
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Credentials {
+///     username: String,
+///     password: Secret<String>,
+/// }
+///
+/// // logs `Credentials { username: "admin", password: [REDACTED] }`
+/// tracing::info!("{credentials:?}");
+///
+/// verify(&credentials.username, credentials.password.expose_secret())?;
+/// ```
+pub struct Secret<V: Zeroize>(V);
+
+impl<V: Zeroize> Secret<V> {
+    /// Creates a new [`Secret`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The sensitive value to wrap.
+    pub fn new(value: V) -> Self {
+        Self(value)
+    }
+
+    /// Borrows a reference to the sensitive value, which should only be
+    /// done where it is actually used.
+    pub fn expose_secret(&self) -> &V {
+        &self.0
+    }
+
+    /// Serializes the sensitive value itself instead of redacting it, to be
+    /// used with `#[serde(serialize_with = "Secret::serialize_exposed")]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret to serialize.
+    /// * `serializer` - The serializer to serialize the value with.
+    #[cfg(feature = "serde")]
+    pub fn serialize_exposed<S>(
+        secret: &Self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        V: serde::Serialize,
+        S: serde::Serializer,
+    {
+        secret.0.serialize(serializer)
+    }
+}
+
+impl<V: Zeroize> From<V> for Secret<V> {
+    fn from(value: V) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<V: Clone + Zeroize> Clone for Secret<V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V: Zeroize> fmt::Debug for Secret<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<V: Zeroize> fmt::Display for Secret<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<V: Zeroize> Drop for Secret<V> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for Secret<V>
+where
+    V: Zeroize + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        V::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reddd_macros::ValueType;
+
+    use super::*;
+    use crate::domain::ValueType;
+
+    #[test]
+    fn secret_test() {
+        let secret = Secret::new("hunter2".to_string());
+
+        assert_eq!("[REDACTED]", format!("{secret:?}"));
+        assert_eq!("[REDACTED]", secret.to_string());
+        assert_eq!("hunter2", *secret.clone().expose_secret());
+    }
+
+    #[test]
+    fn sensitive_value_type_test() {
+        #[derive(Clone, ValueType)]
+        #[value_type(sensitive(serialize = "skip"))]
+        struct Password(String);
+
+        #[derive(Clone, ValueType)]
+        #[value_type(sensitive(serialize = "skip"), compare = "main_field")]
+        struct ApiKey {
+            #[main_field]
+            key: Vec<u8>,
+            name: &'static str,
+        }
+
+        let password = Password("hunter2".into());
+        let key = ApiKey {
+            key: b"secret".to_vec(),
+            name: "ci",
+        };
+
+        assert_eq!("Password([REDACTED])", format!("{password:?}"));
+        assert_eq!("[REDACTED]", password.to_string());
+        assert_eq!("hunter2", *password.as_inner());
+        assert_eq!("hunter2", password.into_inner());
+        assert_eq!("ApiKey([REDACTED])", format!("{key:?}"));
+        assert_eq!("ci", key.name);
+        assert_eq!(b"secret".to_vec(), key.into_inner());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sensitive_value_type_serde_test() {
+        #[derive(Clone, ValueType)]
+        #[value_type(sensitive)]
+        struct Password(String);
+
+        #[derive(Clone, ValueType)]
+        #[value_type(sensitive(serialize = "exposed"))]
+        struct Token(String);
+
+        let err = serde_json::to_value(Password("hunter2".into())).unwrap_err();
+
+        assert_eq!(
+            "`Password` is sensitive and cannot be serialized",
+            err.to_string()
+        );
+        assert_eq!(
+            serde_json::json!("t0k3n"),
+            serde_json::to_value(Token("t0k3n".into())).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct Credentials {
+            username: String,
+            #[serde(skip_serializing)]
+            password: Secret<String>,
+            #[serde(serialize_with = "Secret::serialize_exposed")]
+            token: Secret<String>,
+        }
+
+        let credentials: Credentials =
+            serde_json::from_value(serde_json::json!({
+                "username": "admin",
+                "password": "hunter2",
+                "token": "t0k3n",
+            }))
+            .unwrap();
+
+        assert_eq!("hunter2", *credentials.password.expose_secret());
+        assert_eq!(
+            "Credentials { username: \"admin\", password: [REDACTED], token: \
+             [REDACTED] }",
+            format!("{credentials:?}")
+        );
+        assert_eq!(
+            serde_json::json!({
+                "username": "admin",
+                "token": "t0k3n",
+            }),
+            serde_json::to_value(&credentials).unwrap()
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::{any::type_name, marker::PhantomData};
+
+use tracing::{field, Instrument};
+
+use super::{UseCase, UseCaseHandler};
+
+/// A use case handler decorator that emits a [`tracing`] span for each
+/// execution of an inner handler `H`.
+///
+/// Spans are named `usecase`, and are labeled with the use case type name as
+/// the `usecase` field, as well as the `otel.name` field for OpenTelemetry
+/// exporters. Spans also record the handler type name and the outcome (`ok`
+/// or `error`) of the execution.
+///
+/// # Example
+///
+/// ```ignore
+/// let user = InstrumentedHandler::<RegisterUserHandler>::execute(
+///     input,
+///     &state,
+/// )
+/// .await?;
+/// ```
+pub struct InstrumentedHandler<H>(PhantomData<fn() -> H>);
+
+#[async_trait::async_trait]
+impl<U, S, H> UseCaseHandler<U, S> for InstrumentedHandler<H>
+where
+    U: UseCase,
+    U::Input: Send,
+    S: Sync,
+    H: UseCaseHandler<U, S>,
+{
+    async fn execute(input: U::Input, state: &S) -> Result<U::Output, U::Error>
+    where
+        U::Input: 'async_trait,
+    {
+        let usecase = type_name::<U>();
+        let span = tracing::info_span!(
+            "usecase",
+            otel.name = usecase,
+            usecase,
+            handler = type_name::<H>(),
+            outcome = field::Empty,
+        );
+
+        let result = H::execute(input, state).instrument(span.clone()).await;
+
+        span.record("outcome", if result.is_ok() { "ok" } else { "error" });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reddd_macros::UseCase;
+
+    use super::*;
+    use crate::domain::repo::testing::SpanCapture;
+
+    #[derive(UseCase)]
+    #[usecase(input = "i32", output = "i32", error = "String")]
+    struct Halve;
+
+    struct HalveHandler;
+
+    #[async_trait::async_trait]
+    impl UseCaseHandler<Halve, ()> for HalveHandler {
+        async fn execute(input: i32, _state: &()) -> Result<i32, String> {
+            match input % 2 {
+                | 0 => Ok(input / 2),
+                | _ => Err(format!("{input} is odd")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_span_test() {
+        let capture = SpanCapture::default();
+        let _guard = capture.install();
+
+        type Handler = InstrumentedHandler<HalveHandler>;
+
+        assert_eq!(Ok(2), Handler::execute(4, &()).await);
+        assert!(Handler::execute(3, &()).await.is_err());
+
+        let output = capture.output();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(2, lines.len());
+        assert!(lines[0].contains("usecase{"));
+        assert!(lines[0].contains("::Halve\""));
+        assert!(lines[0].contains("handler=\""));
+        assert!(lines[0].contains("outcome=\"ok\""));
+        assert!(lines[1].contains("outcome=\"error\""));
+    }
+}
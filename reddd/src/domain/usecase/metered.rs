@@ -0,0 +1,109 @@
+use std::{any::type_name, marker::PhantomData, time::Instant};
+
+use super::{UseCase, UseCaseHandler};
+
+/// A use case handler decorator that records statistics of each execution of
+/// an inner handler `H` through the [`metrics`] facade.
+///
+/// The following metrics are recorded, all labeled with the use case type
+/// name as `usecase`:
+///
+/// * `usecase_executions_total` - A counter of executions, additionally labeled
+///   with the `outcome` (`ok` or `error`).
+/// * `usecase_execution_duration_seconds` - A histogram of execution latencies.
+///
+/// # Example
+///
+/// ```ignore
+/// let user =
+///     MeteredHandler::<RegisterUserHandler>::execute(input, &state).await?;
+/// ```
+pub struct MeteredHandler<H>(PhantomData<fn() -> H>);
+
+#[async_trait::async_trait]
+impl<U, S, H> UseCaseHandler<U, S> for MeteredHandler<H>
+where
+    U: UseCase,
+    U::Input: Send,
+    S: Sync,
+    H: UseCaseHandler<U, S>,
+{
+    async fn execute(input: U::Input, state: &S) -> Result<U::Output, U::Error>
+    where
+        U::Input: 'async_trait,
+    {
+        let usecase = type_name::<U>();
+        let started_at = Instant::now();
+        let result = H::execute(input, state).await;
+
+        metrics::histogram!(
+            "usecase_execution_duration_seconds",
+            "usecase" => usecase,
+        )
+        .record(started_at.elapsed());
+
+        metrics::counter!(
+            "usecase_executions_total",
+            "usecase" => usecase,
+            "outcome" => if result.is_ok() { "ok" } else { "error" },
+        )
+        .increment(1);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reddd_macros::UseCase;
+
+    use super::*;
+    use crate::domain::repo::testing::MetricsCapture;
+
+    #[derive(UseCase)]
+    #[usecase(input = "u8", output = "u8", error = "()")]
+    struct Increment;
+
+    struct IncrementHandler;
+
+    #[async_trait::async_trait]
+    impl UseCaseHandler<Increment, ()> for IncrementHandler {
+        async fn execute(input: u8, _state: &()) -> Result<u8, ()> {
+            input.checked_add(1).ok_or(())
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_metrics_test() {
+        let capture = MetricsCapture::new();
+        let _guard = capture.install();
+
+        type Handler = MeteredHandler<IncrementHandler>;
+
+        assert_eq!(Ok(2), Handler::execute(1, &()).await);
+        assert_eq!(Ok(3), Handler::execute(2, &()).await);
+        assert_eq!(Err(()), Handler::execute(u8::MAX, &()).await);
+
+        let metrics = capture.snapshot();
+        let usecase = ("usecase", type_name::<Increment>());
+
+        assert_eq!(
+            2,
+            metrics.counter(
+                "usecase_executions_total",
+                &[usecase, ("outcome", "ok")]
+            )
+        );
+        assert_eq!(
+            1,
+            metrics.counter(
+                "usecase_executions_total",
+                &[usecase, ("outcome", "error")]
+            )
+        );
+        assert_eq!(
+            3,
+            metrics.samples("usecase_execution_duration_seconds", &[usecase])
+        );
+    }
+}
@@ -1,3 +1,13 @@
+#[cfg(feature = "tracing")]
+mod instrumented;
+#[cfg(feature = "metrics")]
+mod metered;
+
+#[cfg(feature = "tracing")]
+pub use instrumented::*;
+#[cfg(feature = "metrics")]
+pub use metered::*;
+
 /// A trait to be implemented by use cases.
 pub trait UseCase {
     /// The input to the use case.
@@ -33,9 +43,11 @@ pub trait UseCaseHandler<U: UseCase, S> {
 
 #[cfg(test)]
 mod tests {
-    use crate::domain::UseCase;
     use reddd_macros::UseCase;
 
+    use crate::domain::UseCase;
+
+    #[allow(dead_code)]
     #[derive(UseCase)]
     #[usecase(input = "String", output = "i32", error = "()")]
     struct SampleUseCase;
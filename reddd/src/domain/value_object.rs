@@ -0,0 +1,186 @@
+/// A trait to be implemented by structural value objects, which are made of
+/// several fields that together form a single value (e.g. addresses).
+///
+/// Unlike [`ValueType`](super::ValueType), which wraps a single main value,
+/// value objects are compared by all of their fields.
+///
+/// When derived, [`PartialEq`] and [`PartialOrd`] implementations that
+/// compare all fields in order are generated, along with a `with_<field>`
+/// method for every field, which returns a validated copy of the value object
+/// with the field replaced. Value objects with fields that cannot be ordered
+/// skip [`PartialOrd`] with `#[value_object(unordered)]`.
+/// Value objects are validated by the functions passed with
+/// `#[value_object(validate = path::to::fn)]`, in order, where each is a
+/// function with the signature `fn(&Self) -> Result<(), impl ToString>`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Clone, Debug, ValueObject)]
+/// #[value_object(validate = has_valid_zip)]
+/// struct Address {
+///     street: String,
+///     city: String,
+///     zip: String,
+/// }
+///
+/// fn has_valid_zip(address: &Address) -> Result<(), &'static str> {
+///     match address.zip.len() == 5 {
+///         | true => Ok(()),
+///         | false => Err("zip codes must have 5 digits"),
+///     }
+/// }
+///
+/// let address = Address { street, city, zip }.validated()?;
+/// let moved = address.with_street("Other St.".to_string())?;
+///
+/// assert_ne!(address, moved);
+/// ```
+pub trait ValueObject: Clone + PartialEq {
+    /// Checks whether the value object is valid.
+    ///
+    /// Value objects are valid by default.
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Validates the value object, and returns it only if it is valid,
+    /// which is useful when constructing value objects.
+    fn validated(self) -> Result<Self, ValidationError> {
+        self.validate().map(|_| self)
+    }
+}
+
+/// A struct that holds info about a value object that failed validation.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("invalid `{value_object}`: {reason}")]
+pub struct ValidationError {
+    /// The name of the value object type.
+    pub value_object: &'static str,
+
+    /// The reason the validation failed for.
+    pub reason: String,
+}
+
+impl ValidationError {
+    /// Creates a new [`ValidationError`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value_object` - The name of the value object type.
+    /// * `reason` - The reason the validation failed for.
+    pub fn new(value_object: &'static str, reason: impl ToString) -> Self {
+        Self {
+            value_object,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reddd_macros::ValueObject;
+
+    use super::*;
+
+    #[derive(Clone, Debug, ValueObject)]
+    #[value_object(validate = has_city, validate = checks::has_valid_zip)]
+    struct Address {
+        street: String,
+        city: String,
+        zip: String,
+    }
+
+    fn has_city(address: &Address) -> Result<(), &'static str> {
+        match address.city.is_empty() {
+            | true => Err("missing city"),
+            | false => Ok(()),
+        }
+    }
+
+    mod checks {
+        pub(super) fn has_valid_zip(
+            address: &super::Address,
+        ) -> Result<(), String> {
+            match address.zip.len() {
+                | 5 => Ok(()),
+                | len => Err(format!("zip code has {len} digits")),
+            }
+        }
+    }
+
+    fn address() -> Address {
+        Address {
+            street: "Main St.".into(),
+            city: "Springfield".into(),
+            zip: "12345".into(),
+        }
+    }
+
+    #[test]
+    fn equality_test() {
+        let address = address();
+
+        assert_eq!(address, address.clone());
+        assert_ne!(address, address.with_street("Other St.".into()).unwrap());
+        assert_ne!(address, address.with_zip("54321".into()).unwrap());
+    }
+
+    #[test]
+    fn ordering_test() {
+        let address = address();
+
+        assert!(address < address.with_zip("54321".into()).unwrap());
+        assert!(address > address.with_city("Shelbyville".into()).unwrap());
+        assert_eq!(
+            Some(std::cmp::Ordering::Equal),
+            address.partial_cmp(&address.clone())
+        );
+    }
+
+    #[test]
+    fn generic_test() {
+        #[derive(Clone, Debug, ValueObject)]
+        struct Range<T> {
+            start: T,
+            end: T,
+        }
+
+        #[derive(Clone, Debug, ValueObject)]
+        #[value_object(unordered)]
+        struct Tags<T: std::hash::Hash + Eq> {
+            tags: std::collections::HashSet<T>,
+        }
+
+        let range = Range {
+            start: 1.5,
+            end: 2.0,
+        };
+
+        assert_eq!(range, range.clone());
+        assert!(range < range.with_end(3.0).unwrap());
+        assert_eq!(
+            None,
+            range.partial_cmp(&range.with_start(f64::NAN).unwrap())
+        );
+
+        let tags = Tags { tags: ["a"].into() };
+
+        assert_ne!(tags, tags.with_tags(["b"].into()).unwrap());
+    }
+
+    #[test]
+    fn validation_test() {
+        let address = address().validated().unwrap();
+
+        assert_eq!(
+            Err(ValidationError::new("Address", "missing city")),
+            address.with_city(String::new())
+        );
+        assert_eq!(
+            "invalid `Address`: zip code has 3 digits",
+            address.with_zip("123".into()).unwrap_err().to_string()
+        );
+        assert_eq!("12345", address.zip);
+    }
+}
@@ -1,6 +1,8 @@
-use std::marker::PhantomData;
+use std::{hash::Hash, marker::PhantomData};
 
 use reddd_macros::ValueType;
+#[cfg(feature = "schemars")]
+pub use schemars::{JsonSchema, Schema, SchemaGenerator};
 
 /// A trait to be implemented by concrete value types.
 ///
@@ -8,13 +10,79 @@ use reddd_macros::ValueType;
 /// within the value type itself. It also can be used to strongly-type values
 /// that could mean different things.
 ///
+/// When derived for C-like enums, the inner value is the name of the variant
+/// (which can be overridden with `#[value_type(name = "...")]`), or its
+/// discriminant when an integer type is given with
+/// `#[value_type(inner = "u8")]`. A `TryFrom` implementation from the inner
+/// value is generated as well.
+///
+/// Numeric value types can derive arithmetic operators on their main field
+/// with `#[value_type(ops(add, sub, mul_scalar, checked))]`, where `add`
+/// also derives [`Sum`](std::iter::Sum), `mul_scalar` derives
+/// multiplication by a value of the inner type, and `checked` derives
+/// `checked_*` variants that return an [`ArithmeticError`] instead of
+/// panicking. Results are checked with `#[value_type(validate = path)]`,
+/// which is required along with `ops(..)` and is a function with the
+/// signature `fn(&Self::Inner) -> Result<(), impl ToString>`, so it should
+/// hold the same constraints as the `TryFrom` implementation of the value
+/// type, if any. Operators are only derived for value types whose other
+/// fields are [`PhantomData`] markers.
+///
+/// Structs with more than one field (not counting [`PhantomData`] markers)
+/// must state how they are compared, either by their main field only with
+/// `#[value_type(compare = "main_field")]`, or by all of their fields with
+/// `#[value_type(compare = "all_fields")]`, in which case they are ordered
+/// by their main field first, then by the other fields in order.
+///
+/// Value types that hold sensitive values (e.g. passwords) can be derived
+/// with `#[value_type(sensitive)]`, which redacts them when debugged or
+/// displayed (so neither `Debug` nor `Display` must be derived), and
+/// zeroizes their main field when dropped, which requires `Zeroize` from the
+/// `secret` feature. Their value is then only exposed through
+/// [`ValueType::as_inner`] and [`ValueType::into_inner`]. They also
+/// implement `Serialize` (which requires `serde`) by failing, so they are
+/// never written out by accident, unless serialization is opted in with
+/// `#[value_type(sensitive(serialize = "exposed"))]`, which serializes the
+/// main field in the clear, or left to the user with
+/// `#[value_type(sensitive(serialize = "skip"))]`, which implements no
+/// `Serialize`.
+///
+/// With the `schemars` feature, `#[value_type(schema)]` derives a
+/// [`JsonSchema`] implementation, which is the one of the main field (or an
+/// enumeration of the inner values for enums). Constraints can be declared
+/// with `#[value_type(schema(min = 0, max = 100))]`, where `min` and `max`
+/// bound numbers, `min_length` and `max_length` bound strings and arrays,
+/// and `pattern` is a regular expression that strings must match.
+///
+/// Likewise, with the `utoipa` feature, `#[value_type(openapi)]` derives
+/// [`ToSchema`](utoipa::ToSchema) and `ValueSchema`, where constraints are
+/// declared with `#[value_type(openapi(..))]` in the same way.
+///
+/// For property-based testing, `#[value_type(proptest)]` and
+/// `#[value_type(arbitrary)]` derive `Arbitrary` implementations of the
+/// `proptest` and `arbitrary` crates, which respect the constraints declared
+/// with `#[value_type(proptest(..))]` and `#[value_type(arbitrary(..))]` in
+/// the same way (except for patterns with `arbitrary`), and the function set
+/// with `#[value_type(validate = path)]`. Values that are out of constraints
+/// are filtered out by `proptest`, and rejected by `arbitrary`.
+///
+/// # Panics
+///
+/// The derived operators and [`Sum`](std::iter::Sum) implementation panic
+/// when the result is rejected by `validate`, which includes the sum of no
+/// values when zero is rejected, and overflow as the inner type does (i.e.
+/// panic in debug builds). Use the `checked_*` variants derived with
+/// `ops(checked)` where results can be invalid.
+///
 /// # Example
 ///
 /// ```rust
 /// use reddd::domain::ValueType;
 ///
-/// const MIN_ALLOWED_BALANCE: f64 = 0.00;
-/// const MAX_ALLOWED_BALANCE: f64 = 1_000_000.00;
+/// // in cents, as floating-point numbers are not suitable for money (see
+/// // `values::Money` of the `money` feature for a complete implementation)
+/// const MIN_ALLOWED_BALANCE: i64 = 0;
+/// const MAX_ALLOWED_BALANCE: i64 = 100_000_000;
 ///
 /// enum BalanceError {
 ///     BalanceOutOfBounds,
@@ -22,10 +90,10 @@ use reddd_macros::ValueType;
 /// }
 ///
 /// #[derive(Clone)]
-/// struct Balance(f64);
+/// struct Balance(i64);
 ///
 /// impl ValueType for Balance {
-///     type Inner = f64;
+///     type Inner = i64;
 ///
 ///     fn into_inner(self) -> Self::Inner {
 ///         self.0
@@ -36,10 +104,10 @@ use reddd_macros::ValueType;
 ///     }
 /// }
 ///
-/// impl TryFrom<f64> for Balance {
+/// impl TryFrom<i64> for Balance {
 ///     type Error = BalanceError;
 ///
-///     fn try_from(value: f64) -> Result<Self, Self::Error> {
+///     fn try_from(value: i64) -> Result<Self, Self::Error> {
 ///         if value < MIN_ALLOWED_BALANCE || value > MAX_ALLOWED_BALANCE {
 ///             return Err(BalanceError::BalanceOutOfBounds);
 ///         }
@@ -58,6 +126,19 @@ pub trait ValueType: Clone {
     fn as_inner(&self) -> &Self::Inner;
 }
 
+/// An enumeration of possible errors that can occur when applying
+/// arithmetic operators on value types.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ArithmeticError {
+    /// The result does not fit in the inner type.
+    #[error("arithmetic overflow")]
+    Overflow,
+
+    /// The result does not satisfy the validation of the value type.
+    #[error("invalid result: {0}")]
+    Invalid(String),
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "serde")] {
         /// A serde-compatible value of type `V` wrapper that is typed to a
@@ -130,7 +211,7 @@ cfg_if::cfg_if! {
         /// }
         /// ````
         #[derive(Debug, ValueType)]
-        pub struct TypedValue<V, T>(#[main_field] V, PhantomData<dyn Fn() -> T>)
+        pub struct TypedValue<V, T>(#[main_field] V, PhantomData<fn() -> T>)
         where
             V: Clone + std::fmt::Debug + PartialEq + PartialOrd;
     }
@@ -154,6 +235,42 @@ where
     }
 }
 
+impl<V, T> Eq for TypedValue<V, T> where
+    V: Clone + std::fmt::Debug + Eq + PartialOrd
+{
+}
+
+impl<V, T> Hash for TypedValue<V, T>
+where
+    V: Clone + std::fmt::Debug + Hash + PartialEq + PartialOrd,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<V, T> JsonSchema for TypedValue<V, T>
+where
+    V: Clone + std::fmt::Debug + PartialEq + PartialOrd + JsonSchema,
+{
+    fn inline_schema() -> bool {
+        V::inline_schema()
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        V::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        V::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        V::json_schema(generator)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use fake::{Dummy, Fake, Faker};
@@ -188,7 +305,8 @@ mod tests {
     #[test]
     fn multifield_newtype_annotated_test() {
         #[derive(Clone, ValueType, Dummy)]
-        struct NewType(i32, #[main_field] String);
+        #[value_type(compare = "main_field")]
+        struct NewType((), #[main_field] String);
 
         let f: NewType = Faker.fake();
         let fval = f.1.clone();
@@ -200,7 +318,8 @@ mod tests {
     #[test]
     fn multifield_newtype_auto_test() {
         #[derive(Clone, ValueType, Dummy)]
-        struct NewType(i32, String);
+        #[value_type(compare = "main_field")]
+        struct NewType(i32, ());
 
         let f: NewType = Faker.fake();
         let fval = f.0;
@@ -212,6 +331,7 @@ mod tests {
     #[test]
     fn multifield_named_annotated_test() {
         #[derive(Clone, ValueType, Dummy)]
+        #[value_type(compare = "main_field")]
         struct NewType {
             _field0: i32,
             #[main_field]
@@ -228,6 +348,7 @@ mod tests {
     #[test]
     fn multifield_named_auto_test() {
         #[derive(Clone, ValueType, Dummy)]
+        #[value_type(compare = "main_field")]
         struct NewType {
             field0: i32,
             _field1: String,
@@ -240,6 +361,26 @@ mod tests {
         assert_eq!(fval, f.into_inner());
     }
 
+    #[test]
+    fn multifield_compare_test() {
+        #[allow(dead_code)]
+        #[derive(Clone, Debug, ValueType)]
+        #[value_type(compare = "main_field")]
+        struct MainField(i32, &'static str);
+
+        #[derive(Clone, Debug, ValueType)]
+        #[value_type(compare = "all_fields")]
+        struct AllFields(i32, &'static str);
+
+        assert_eq!(MainField(1, "a"), MainField(1, "b"));
+        assert!(MainField(1, "b") < MainField(2, "a"));
+
+        assert_ne!(AllFields(1, "a"), AllFields(1, "b"));
+        assert_eq!(AllFields(1, "a"), AllFields(1, "a"));
+        assert!(AllFields(1, "a") < AllFields(1, "b"));
+        assert!(AllFields(1, "b") < AllFields(2, "a"));
+    }
+
     #[test]
     fn equlity_test() {
         #[derive(Clone, Debug, ValueType, Dummy)]
@@ -293,6 +434,116 @@ mod tests {
         assert!(f2 >= f1);
     }
 
+    #[test]
+    fn ops_test() {
+        #[derive(Clone, Copy, Debug, ValueType)]
+        #[value_type(ops(add, sub, mul_scalar, checked), validate = at_most_100)]
+        struct Quantity(u32);
+
+        fn at_most_100(value: &u32) -> Result<(), &'static str> {
+            match *value <= 100 {
+                | true => Ok(()),
+                | false => Err("quantities are at most 100"),
+            }
+        }
+
+        let (q1, q2) = (Quantity(30), Quantity(20));
+
+        assert_eq!(Quantity(50), q1 + q2);
+        assert_eq!(Quantity(10), q1 - q2);
+        assert_eq!(Quantity(90), q1 * 3);
+        assert_eq!(Quantity(60), [q1, q1].into_iter().sum());
+        assert_eq!(Ok(Quantity(50)), q1.checked_add(q2));
+        assert_eq!(Err(ArithmeticError::Overflow), q2.checked_sub(q1));
+        assert_eq!(
+            Err(ArithmeticError::Invalid(
+                "quantities are at most 100".into()
+            )),
+            q1.checked_mul(4)
+        );
+        assert_eq!(Ok(Quantity(80)), Quantity::checked_sum([q1, q1, q2]));
+        assert!(Quantity::checked_sum([q1; 4]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid result of `add` on `Quantity`")]
+    fn ops_validation_panic_test() {
+        #[derive(Clone, Copy, Debug, ValueType)]
+        #[value_type(ops(add), validate = checks::at_most_100)]
+        struct Quantity {
+            value: u8,
+            _unit: PhantomData<()>,
+        }
+
+        mod checks {
+            pub(super) fn at_most_100(value: &u8) -> Result<(), String> {
+                match *value <= 100 {
+                    | true => Ok(()),
+                    | false => Err(format!("{value} is more than 100")),
+                }
+            }
+        }
+
+        let q = Quantity {
+            value: 60,
+            _unit: PhantomData,
+        };
+
+        let _ = q + q;
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid result of `sum` on `Quantity`")]
+    fn ops_empty_sum_panic_test() {
+        #[derive(Clone, Copy, Debug, ValueType)]
+        #[value_type(ops(add, checked), validate = positive)]
+        struct Quantity(u32);
+
+        fn positive(value: &u32) -> Result<(), &'static str> {
+            match *value > 0 {
+                | true => Ok(()),
+                | false => Err("quantities are positive"),
+            }
+        }
+
+        assert!(Quantity::checked_sum([]).is_err());
+
+        let _: Quantity = std::iter::empty().sum();
+    }
+
+    #[test]
+    fn enum_named_test() {
+        #[derive(Clone, Copy, Debug, ValueType)]
+        enum Status {
+            Active,
+            #[value_type(name = "on_hold")]
+            OnHold,
+        }
+
+        assert_eq!(&"Active", Status::Active.as_inner());
+        assert_eq!("on_hold", Status::OnHold.into_inner());
+        assert_eq!(Ok(Status::OnHold), Status::try_from("on_hold"));
+        assert_eq!(Err("OnHold"), Status::try_from("OnHold"));
+        assert!(Status::Active < Status::OnHold);
+    }
+
+    #[test]
+    fn enum_discriminant_test() {
+        #[derive(Clone, Copy, Debug, ValueType)]
+        #[value_type(inner = "u8")]
+        enum Priority {
+            Low = 1,
+            Normal,
+            High = 10,
+        }
+
+        assert_eq!(&1, Priority::Low.as_inner());
+        assert_eq!(2, Priority::Normal.into_inner());
+        assert_eq!(Ok(Priority::High), Priority::try_from(10));
+        assert_eq!(Err(3), Priority::try_from(3));
+        assert!(Priority::High > Priority::Normal);
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn serde_test() {
@@ -314,4 +565,80 @@ mod tests {
         assert_eq!(value.as_inner(), deserialized.as_inner());
         assert_eq!(value.into_inner(), deserialized.into_inner());
     }
+
+    #[test]
+    #[cfg(feature = "schemars")]
+    fn schema_test() {
+        struct SomeType;
+
+        #[derive(Clone, ValueType)]
+        #[value_type(schema(min = 0, max = 100))]
+        struct Percentage(u8);
+
+        #[derive(Clone, ValueType)]
+        #[value_type(schema(
+            min_length = 1,
+            max_length = 3,
+            pattern = "^[a-z]+$"
+        ))]
+        struct Tag(String);
+
+        #[derive(Clone, ValueType)]
+        #[value_type(schema(max_length = 2))]
+        struct Tags(Vec<String>);
+
+        #[derive(Clone, Copy, ValueType)]
+        #[value_type(schema)]
+        enum Status {
+            Active,
+            #[value_type(name = "closed")]
+            Closed,
+        }
+
+        #[derive(Clone, Copy, ValueType)]
+        #[value_type(inner = "u8", schema)]
+        enum Priority {
+            Low = 1,
+            High = 3,
+        }
+
+        assert_eq!(
+            schemars::schema_for!(u64),
+            schemars::schema_for!(TypedValue<u64, SomeType>)
+        );
+        assert_eq!(
+            serde_json::json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "Percentage",
+                "type": "integer",
+                "format": "uint8",
+                "minimum": 0,
+                "maximum": 100,
+            }),
+            schemars::schema_for!(Percentage).to_value()
+        );
+        assert_eq!(
+            serde_json::json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "Tag",
+                "type": "string",
+                "minLength": 1,
+                "maxLength": 3,
+                "pattern": "^[a-z]+$",
+            }),
+            schemars::schema_for!(Tag).to_value()
+        );
+        assert_eq!(
+            Some(&serde_json::json!(2)),
+            schemars::schema_for!(Tags).get("maxItems")
+        );
+        assert_eq!(
+            Some(&serde_json::json!(["Active", "closed"])),
+            schemars::schema_for!(Status).get("enum")
+        );
+        assert_eq!(
+            Some(&serde_json::json!([1, 3])),
+            schemars::schema_for!(Priority).get("enum")
+        );
+    }
 }
@@ -0,0 +1,73 @@
+//! Ready-made value types of common domain concepts.
+//!
+//! All value types of this module are validated on construction, whether
+//! through [`TryFrom`], [`FromStr`](std::str::FromStr) or deserialization,
+//! so holding one is a proof that its value is valid.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use reddd::domain::values::{EmailAddress, NonEmptyString};
+//!
+//! #[derive(MutableEntity, EntityBuilder)]
+//! struct User {
+//!     id: Key<User, Uuid>,
+//!     created_at: DateTime<Utc>,
+//!     updated_at: DateTime<Utc>,
+//!     name: NonEmptyString,
+//!     email: EmailAddress,
+//! }
+//!
+//! let user = User::builder()
+//!     .name("John")
+//!     .email("john@example.com")
+//!     .build_with(&|| Key::new(Uuid::new_v4()), &SystemClock)?;
+//! ```
+
+#[cfg(feature = "money")]
+mod money;
+mod numeric;
+mod text;
+pub mod time;
+
+#[cfg(feature = "money")]
+pub use money::*;
+pub use numeric::*;
+pub use text::*;
+
+/// An enumeration of possible errors that can occur when validating values.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ValueError {
+    /// An empty value was provided.
+    #[error("value must not be empty")]
+    Empty,
+
+    /// A value that is not well-formed was provided.
+    #[error("invalid {kind}: {reason}")]
+    Invalid {
+        /// The kind of the value.
+        kind: &'static str,
+
+        /// The reason the value is invalid for.
+        reason: String,
+    },
+
+    /// A value that is out of the allowed range was provided.
+    #[error("value `{value}` is out of range, expected {expected}")]
+    OutOfRange {
+        /// The provided value.
+        value: String,
+
+        /// A description of the allowed range.
+        expected: &'static str,
+    },
+}
+
+impl ValueError {
+    fn invalid(kind: &'static str, reason: impl ToString) -> Self {
+        Self::Invalid {
+            kind,
+            reason: reason.to_string(),
+        }
+    }
+}
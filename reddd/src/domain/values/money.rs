@@ -0,0 +1,646 @@
+use std::{cmp::Ordering, fmt, hash::Hash, str::FromStr};
+
+use reddd_macros::ValueType;
+pub use rust_decimal::{Decimal, RoundingStrategy};
+
+use super::ValueError;
+use crate::domain::ValueType;
+
+macro_rules! currencies {
+    ($($code:ident $numeric:literal $minor_units:literal),* $(,)?) => {
+        impl Currency {
+            $(
+                #[doc = concat!("The `", stringify!($code), "` currency.")]
+                pub const $code: Self = Self {
+                    code: stringify!($code),
+                    numeric: $numeric,
+                    minor_units: $minor_units,
+                };
+            )*
+        }
+
+        const CURRENCIES: &[Currency] = &[$(Currency::$code),*];
+    };
+}
+
+/// An ISO-4217 currency.
+///
+/// All active circulating currencies are supported, and are available as
+/// associated constants (e.g. [`Currency::USD`]).
+#[derive(Clone, Copy, Debug, ValueType)]
+#[value_type(compare = "main_field")]
+pub struct Currency {
+    #[main_field]
+    code: &'static str,
+    numeric: u16,
+    minor_units: u32,
+}
+
+currencies! {
+    AED 784 2, AFN 971 2, ALL 8 2, AMD 51 2, ANG 532 2, AOA 973 2,
+    ARS 32 2, AUD 36 2, AWG 533 2, AZN 944 2, BAM 977 2, BBD 52 2,
+    BDT 50 2, BGN 975 2, BHD 48 3, BIF 108 0, BMD 60 2, BND 96 2,
+    BOB 68 2, BRL 986 2, BSD 44 2, BTN 64 2, BWP 72 2, BYN 933 2,
+    BZD 84 2, CAD 124 2, CDF 976 2, CHF 756 2, CLP 152 0, CNY 156 2,
+    COP 170 2, CRC 188 2, CUP 192 2, CVE 132 2, CZK 203 2, DJF 262 0,
+    DKK 208 2, DOP 214 2, DZD 12 2, EGP 818 2, ERN 232 2, ETB 230 2,
+    EUR 978 2, FJD 242 2, FKP 238 2, GBP 826 2, GEL 981 2, GHS 936 2,
+    GIP 292 2, GMD 270 2, GNF 324 0, GTQ 320 2, GYD 328 2, HKD 344 2,
+    HNL 340 2, HTG 332 2, HUF 348 2, IDR 360 2, ILS 376 2, INR 356 2,
+    IQD 368 3, IRR 364 2, ISK 352 0, JMD 388 2, JOD 400 3, JPY 392 0,
+    KES 404 2, KGS 417 2, KHR 116 2, KMF 174 0, KPW 408 2, KRW 410 0,
+    KWD 414 3, KYD 136 2, KZT 398 2, LAK 418 2, LBP 422 2, LKR 144 2,
+    LRD 430 2, LSL 426 2, LYD 434 3, MAD 504 2, MDL 498 2, MGA 969 2,
+    MKD 807 2, MMK 104 2, MNT 496 2, MOP 446 2, MRU 929 2, MUR 480 2,
+    MVR 462 2, MWK 454 2, MXN 484 2, MYR 458 2, MZN 943 2, NAD 516 2,
+    NGN 566 2, NIO 558 2, NOK 578 2, NPR 524 2, NZD 554 2, OMR 512 3,
+    PAB 590 2, PEN 604 2, PGK 598 2, PHP 608 2, PKR 586 2, PLN 985 2,
+    PYG 600 0, QAR 634 2, RON 946 2, RSD 941 2, RUB 643 2, RWF 646 0,
+    SAR 682 2, SBD 90 2, SCR 690 2, SDG 938 2, SEK 752 2, SGD 702 2,
+    SHP 654 2, SLE 925 2, SOS 706 2, SRD 968 2, SSP 728 2, STN 930 2,
+    SVC 222 2, SYP 760 2, SZL 748 2, THB 764 2, TJS 972 2, TMT 934 2,
+    TND 788 3, TOP 776 2, TRY 949 2, TTD 780 2, TWD 901 2, TZS 834 2,
+    UAH 980 2, UGX 800 0, USD 840 2, UYU 858 2, UZS 860 2, VES 928 2,
+    VND 704 0, VUV 548 0, WST 882 2, XAF 950 0, XCD 951 2, XCG 532 2,
+    XOF 952 0, XPF 953 0, YER 886 2, ZAR 710 2, ZMW 967 2, ZWG 924 2,
+}
+
+impl Currency {
+    /// Gets a currency by its alphabetic code (e.g. `USD`), ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The alphabetic code of the currency.
+    pub fn from_code(code: &str) -> Result<Self, ValueError> {
+        let code = code.trim().to_ascii_uppercase();
+
+        CURRENCIES
+            .binary_search_by(|c| c.code.cmp(code.as_str()))
+            .map(|i| CURRENCIES[i])
+            .map_err(|_| ValueError::invalid("currency", "unknown code"))
+    }
+
+    /// Gets all supported currencies, ordered by code.
+    pub fn all() -> &'static [Currency] {
+        CURRENCIES
+    }
+
+    /// Gets the alphabetic code of the currency (e.g. `USD`).
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Gets the numeric code of the currency (e.g. `840` for `USD`).
+    pub fn numeric(&self) -> u16 {
+        self.numeric
+    }
+
+    /// Gets the number of digits after the decimal separator of the
+    /// currency (e.g. `2` for `USD`, where 1 dollar is 100 cents).
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units
+    }
+}
+
+impl TryFrom<&str> for Currency {
+    type Error = ValueError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_code(value)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = ValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_code(&value)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_code(s)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.code.into()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.code)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+
+        Self::from_code(&code).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Eq for Currency {}
+
+impl Ord for Currency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.code.cmp(other.code)
+    }
+}
+
+impl Hash for Currency {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.code.hash(state);
+    }
+}
+
+/// An enumeration of possible errors that can occur when operating on
+/// [`Money`].
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum MoneyError {
+    /// Amounts of different currencies were mixed.
+    #[error("currency mismatch: expected `{expected}`, found `{found}`")]
+    CurrencyMismatch {
+        /// The currency of the left-hand side amount.
+        expected: Currency,
+
+        /// The currency of the right-hand side amount.
+        found: Currency,
+    },
+
+    /// The result of an operation does not fit in an amount.
+    #[error("arithmetic overflow")]
+    Overflow,
+
+    /// An amount was divided by zero.
+    #[error("division by zero")]
+    DivisionByZero,
+
+    /// An amount was allocated with no ratios, or with zero ratios only.
+    #[error("allocation ratios must not be empty nor all zeros")]
+    InvalidRatios,
+}
+
+/// An amount of money in a specific [`Currency`].
+///
+/// Amounts are decimals, so they never suffer from the rounding errors of
+/// floating-point numbers. Operations between amounts are checked, and fail
+/// if the amounts are of different currencies instead of silently mixing
+/// them, which is also why amounts of different currencies are unordered.
+///
+/// # Example
+///
+/// ```ignore
+/// let price = Money::new(Decimal::new(1999, 2), Currency::USD);
+/// let shipping: Money = "5.00 USD".parse()?;
+///
+/// let total = price.checked_add(shipping)?;
+/// let shares = total.allocate(&[1, 2])?;
+///
+/// assert_eq!("24.99 USD", total.to_string());
+/// assert_eq!("8.33 USD", shares[0].to_string());
+/// assert_eq!("16.66 USD", shares[1].to_string());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates a new amount of money.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The decimal amount, in major units (e.g. dollars).
+    /// * `currency` - The currency of the amount.
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Creates a zero amount of money.
+    ///
+    /// # Arguments
+    ///
+    /// * `currency` - The currency of the amount.
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// Creates a new amount of money from minor units (e.g. cents).
+    ///
+    /// # Arguments
+    ///
+    /// * `minor` - The amount in minor units.
+    /// * `currency` - The currency of the amount.
+    pub fn from_minor(minor: i64, currency: Currency) -> Self {
+        Self::new(Decimal::new(minor, currency.minor_units), currency)
+    }
+
+    /// Gets the decimal amount, in major units.
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Gets the currency of the amount.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Gets the amount in minor units, after rounding it to the minor units
+    /// of its currency, or [`None`] if it does not fit in an [`i64`].
+    pub fn to_minor(&self) -> Option<i64> {
+        let mut amount = self.round().amount;
+
+        amount.rescale(self.currency.minor_units);
+
+        i64::try_from(amount.mantissa()).ok()
+    }
+
+    /// Checks whether the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Checks whether the amount is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.amount > Decimal::ZERO
+    }
+
+    /// Checks whether the amount is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.amount < Decimal::ZERO
+    }
+
+    /// Adds `other` to the amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The amount to add, which must be of the same currency.
+    pub fn checked_add(&self, other: Money) -> Result<Money, MoneyError> {
+        self.ensure_same_currency(&other)?;
+
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Subtracts `other` from the amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The amount to subtract, which must be of the same currency.
+    pub fn checked_sub(&self, other: Money) -> Result<Money, MoneyError> {
+        self.ensure_same_currency(&other)?;
+
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Multiplies the amount by `factor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The factor to multiply the amount by.
+    pub fn checked_mul(&self, factor: Decimal) -> Result<Money, MoneyError> {
+        self.amount
+            .checked_mul(factor)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Divides the amount by `divisor`.
+    ///
+    /// Prefer [`Money::allocate`] to split amounts, as it never loses minor
+    /// units.
+    ///
+    /// # Arguments
+    ///
+    /// * `divisor` - The divisor to divide the amount by.
+    pub fn checked_div(&self, divisor: Decimal) -> Result<Money, MoneyError> {
+        if divisor.is_zero() {
+            return Err(MoneyError::DivisionByZero);
+        }
+
+        self.amount
+            .checked_div(divisor)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Rounds the amount to the minor units of its currency, with midpoints
+    /// rounded to the nearest even number (i.e. banker's rounding).
+    pub fn round(&self) -> Money {
+        self.round_with(RoundingStrategy::MidpointNearestEven)
+    }
+
+    /// Rounds the amount to the minor units of its currency.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to round the amount with.
+    pub fn round_with(&self, strategy: RoundingStrategy) -> Money {
+        Self::new(
+            self.amount
+                .round_dp_with_strategy(self.currency.minor_units, strategy),
+            self.currency,
+        )
+    }
+
+    /// Allocates the amount into parts proportional to `ratios`, without
+    /// losing any minor units.
+    ///
+    /// The amount is rounded to the minor units of its currency first, and
+    /// the minor units that are left over after proportional allocation are
+    /// distributed one by one to the parts with the largest fractional
+    /// remainders, and earlier parts on ties. Parts with a zero ratio are
+    /// always zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratios` - The ratios to allocate the amount by.
+    pub fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
+        let total: u64 = ratios.iter().map(|r| u64::from(*r)).sum();
+
+        if total == 0 {
+            return Err(MoneyError::InvalidRatios);
+        }
+
+        let total = Decimal::from(total);
+        let unit = Decimal::new(1, self.currency.minor_units);
+        let units = self
+            .round()
+            .amount
+            .checked_div(unit)
+            .ok_or(MoneyError::Overflow)?;
+
+        // each part along with the remainder of its proportional division,
+        // which has the sign of the amount
+        let mut parts: Vec<(Decimal, Decimal)> = ratios
+            .iter()
+            .map(|ratio| {
+                let share = units
+                    .checked_mul(Decimal::from(*ratio))
+                    .ok_or(MoneyError::Overflow)?;
+                let rest = share % total;
+
+                Ok(((share - rest) / total, rest))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let remainder =
+            units - parts.iter().map(|(part, _)| part).sum::<Decimal>();
+        let step = match remainder.is_sign_negative() {
+            | true => Decimal::NEGATIVE_ONE,
+            | false => Decimal::ONE,
+        };
+
+        // there are fewer left over units than parts with non-zero ratios,
+        // as each of their remainders is less than the total of ratios
+        let mut order: Vec<_> = (0..parts.len())
+            .filter(|i| !parts[*i].1.is_zero())
+            .collect();
+
+        order.sort_by(|a, b| parts[*b].1.abs().cmp(&parts[*a].1.abs()));
+
+        for i in order
+            .into_iter()
+            .take(remainder.abs().try_into().unwrap_or(0))
+        {
+            parts[i].0 += step;
+        }
+
+        Ok(parts
+            .into_iter()
+            .map(|(part, _)| Self::new(part * unit, self.currency))
+            .collect())
+    }
+
+    /// Splits the amount into `count` equal parts, without losing any minor
+    /// units.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of parts to split the amount into.
+    pub fn split(&self, count: usize) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![1; count])
+    }
+
+    fn ensure_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        match self.currency == other.currency {
+            | true => Ok(()),
+            | false => Err(MoneyError::CurrencyMismatch {
+                expected: self.currency,
+                found: other.currency,
+            }),
+        }
+    }
+}
+
+impl std::ops::Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.amount, self.currency)
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.currency == other.currency {
+            | true => self.amount.partial_cmp(&other.amount),
+            | false => None,
+        }
+    }
+}
+
+impl FromStr for Money {
+    type Err = ValueError;
+
+    /// Parses an amount followed by a currency code (e.g. `12.50 USD`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (amount, currency) = s
+            .trim()
+            .rsplit_once(' ')
+            .ok_or_else(|| ValueError::invalid("money", "missing currency"))?;
+
+        let amount = Decimal::from_str_exact(amount.trim())
+            .map_err(|err| ValueError::invalid("money", err))?;
+
+        Ok(Self::new(amount, currency.parse()?))
+    }
+}
+
+impl fmt::Display for Money {
+    /// Formats the amount with at least the minor units of its currency,
+    /// followed by its code (e.g. `12.50 USD`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = self.amount.scale().max(self.currency.minor_units);
+
+        write!(f, "{:.*} {}", scale as usize, self.amount, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(minor: i64) -> Money {
+        Money::from_minor(minor, Currency::USD)
+    }
+
+    #[test]
+    fn currency_test() {
+        assert!(Currency::all().windows(2).all(|w| w[0].code < w[1].code));
+        assert_eq!(Ok(Currency::USD), "usd".parse());
+        assert_eq!(840, Currency::USD.numeric());
+        assert_eq!(0, Currency::JPY.minor_units());
+        assert_eq!(&"KWD", Currency::KWD.as_inner());
+        assert!(Currency::from_code("XYZ").is_err());
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let total = usd(1999).checked_add(usd(501)).unwrap();
+
+        assert_eq!(usd(2500), total);
+        assert_eq!(Ok(usd(-1)), usd(1).checked_sub(usd(2)));
+        assert_eq!(Ok(usd(5000)), total.checked_mul(Decimal::TWO));
+        assert_eq!(
+            Err(MoneyError::DivisionByZero),
+            total.checked_div(Decimal::ZERO)
+        );
+        assert_eq!(
+            Err(MoneyError::CurrencyMismatch {
+                expected: Currency::USD,
+                found: Currency::EUR,
+            }),
+            total.checked_add(Money::zero(Currency::EUR))
+        );
+        assert!(usd(1) < usd(2));
+        assert_eq!(None, usd(1).partial_cmp(&Money::zero(Currency::EUR)));
+        assert!((-usd(1)).is_negative());
+    }
+
+    #[test]
+    fn rounding_test() {
+        let amount = Money::new(Decimal::new(10125, 3), Currency::USD);
+
+        assert_eq!(usd(1012), amount.round());
+        assert_eq!(
+            usd(1013),
+            amount.round_with(RoundingStrategy::MidpointAwayFromZero)
+        );
+        assert_eq!(Some(1012), amount.to_minor());
+        assert_eq!(Some(5), Money::from_minor(5, Currency::JPY).to_minor());
+    }
+
+    #[test]
+    fn allocate_test() {
+        assert_eq!(vec![usd(34), usd(33), usd(33)], usd(100).split(3).unwrap());
+        assert_eq!(
+            vec![usd(-34), usd(-33), usd(-33)],
+            usd(-100).split(3).unwrap()
+        );
+        assert_eq!(
+            vec![usd(7), usd(3), usd(0)],
+            usd(10).allocate(&[70, 30, 0]).unwrap()
+        );
+        assert_eq!(
+            vec![usd(2), usd(1)],
+            Money::new(Decimal::new(299, 4), Currency::USD)
+                .allocate(&[1, 1])
+                .unwrap()
+        );
+        assert_eq!(Err(MoneyError::InvalidRatios), usd(10).allocate(&[0]));
+        assert_eq!(Err(MoneyError::InvalidRatios), usd(10).split(0));
+    }
+
+    #[test]
+    fn allocate_remainder_test() {
+        assert_eq!(
+            vec![usd(0), usd(1), usd(0)],
+            usd(1).allocate(&[0, 1, 1]).unwrap()
+        );
+        assert_eq!(vec![usd(0), usd(-1)], usd(-1).allocate(&[0, 1]).unwrap());
+        assert_eq!(
+            vec![usd(14), usd(14), usd(72)],
+            usd(100).allocate(&[1, 1, 5]).unwrap()
+        );
+        assert_eq!(
+            vec![usd(-14), usd(-14), usd(-72)],
+            usd(-100).allocate(&[1, 1, 5]).unwrap()
+        );
+    }
+
+    #[test]
+    fn allocate_overflow_test() {
+        assert_eq!(
+            Err(MoneyError::Overflow),
+            Money::new(Decimal::MAX, Currency::USD).allocate(&[1, 1])
+        );
+        assert_eq!(
+            Err(MoneyError::Overflow),
+            Money::new(Decimal::MAX, Currency::JPY).allocate(&[1, u32::MAX])
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!("12.50 USD", usd(1250).to_string());
+        assert_eq!(
+            "12.505 USD",
+            "12.505 usd".parse::<Money>().unwrap().to_string()
+        );
+        assert_eq!(
+            "100 JPY",
+            Money::from_minor(100, Currency::JPY).to_string()
+        );
+        assert_eq!(
+            "1.000 KWD",
+            Money::from_minor(1000, Currency::KWD).to_string()
+        );
+        assert!("12.50".parse::<Money>().is_err());
+        assert!("abc USD".parse::<Money>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let serialized = serde_json::to_value(usd(1250)).unwrap();
+
+        assert_eq!(
+            serde_json::json!({ "amount": "12.50", "currency": "USD" }),
+            serialized
+        );
+        assert_eq!(usd(1250), serde_json::from_value(serialized).unwrap());
+        assert!(serde_json::from_value::<Money>(
+            serde_json::json!({ "amount": "1", "currency": "XYZ" })
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use std::{fmt, str::FromStr};
+
+use reddd_macros::ValueType;
+
+use super::ValueError;
+use crate::domain::ValueType;
+
+/// A percentage between 0 and 100, inclusive (e.g. discounts and tax
+/// rates).
+#[derive(Clone, Copy, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "f64", into = "f64")
+)]
+pub struct Percentage(f64);
+
+impl Percentage {
+    /// Creates a new percentage from a fraction between 0 and 1 (e.g. `0.25`
+    /// for 25%).
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - The fraction to create the percentage from.
+    pub fn from_fraction(fraction: f64) -> Result<Self, ValueError> {
+        Self::try_from(fraction * 100.0)
+    }
+
+    /// Gets the percentage as a fraction between 0 and 1.
+    pub fn as_fraction(&self) -> f64 {
+        self.0 / 100.0
+    }
+
+    /// Computes the percentage of `amount`.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to compute the percentage of.
+    pub fn of(&self, amount: f64) -> f64 {
+        amount * self.as_fraction()
+    }
+}
+
+impl TryFrom<f64> for Percentage {
+    type Error = ValueError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        match (0.0..=100.0).contains(&value) {
+            | true => Ok(Self(value)),
+            | false => Err(ValueError::OutOfRange {
+                value: value.to_string(),
+                expected: "a percentage between 0 and 100",
+            }),
+        }
+    }
+}
+
+impl From<Percentage> for f64 {
+    fn from(value: Percentage) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for Percentage {
+    type Err = ValueError;
+
+    /// Parses a percentage, with or without a `%` suffix (e.g. `12.5%`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        s.strip_suffix('%')
+            .unwrap_or(s)
+            .trim_end()
+            .parse::<f64>()
+            .map_err(|err| ValueError::invalid("percentage", err))
+            .and_then(Self::try_from)
+    }
+}
+
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+/// An integer greater than zero (e.g. quantities and counts).
+#[derive(Clone, Copy, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u64", into = "u64")
+)]
+pub struct PositiveInt(u64);
+
+impl TryFrom<u64> for PositiveInt {
+    type Error = ValueError;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value {
+            | 0 => Err(ValueError::OutOfRange {
+                value: value.to_string(),
+                expected: "a positive integer",
+            }),
+            | _ => Ok(Self(value)),
+        }
+    }
+}
+
+impl From<PositiveInt> for u64 {
+    fn from(value: PositiveInt) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for PositiveInt {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u64>()
+            .map_err(|err| ValueError::invalid("positive integer", err))
+            .and_then(Self::try_from)
+    }
+}
+
+impl fmt::Display for PositiveInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Eq for PositiveInt {}
+
+impl std::hash::Hash for PositiveInt {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentage_test() {
+        let discount: Percentage = "12.5 %".parse().unwrap();
+
+        assert_eq!(&12.5, discount.as_inner());
+        assert_eq!(0.125, discount.as_fraction());
+        assert_eq!(25.0, discount.of(200.0));
+        assert_eq!("12.5%", discount.to_string());
+        assert_eq!(Ok(discount), Percentage::from_fraction(0.125));
+        assert_eq!(Ok(discount), "12.5".parse());
+
+        for invalid in [-1.0, 100.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                Percentage::try_from(invalid),
+                Err(ValueError::OutOfRange { .. })
+            ));
+        }
+
+        assert!(matches!(
+            "half".parse::<Percentage>(),
+            Err(ValueError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn positive_int_test() {
+        let quantity: PositiveInt = "42".parse().unwrap();
+
+        assert_eq!(42, quantity.into_inner());
+        assert_eq!("42", quantity.to_string());
+        assert!(quantity > PositiveInt::try_from(1).unwrap());
+        assert!(matches!(
+            PositiveInt::try_from(0),
+            Err(ValueError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            "-1".parse::<PositiveInt>(),
+            Err(ValueError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let quantity: PositiveInt = serde_json::from_str("3").unwrap();
+
+        assert_eq!("3", serde_json::to_string(&quantity).unwrap());
+        assert!(serde_json::from_str::<PositiveInt>("0").is_err());
+        assert!(serde_json::from_str::<Percentage>("101").is_err());
+        assert_eq!(
+            Percentage::try_from(5.0).unwrap(),
+            serde_json::from_str::<Percentage>("5.0").unwrap()
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use std::{fmt, hash::Hash, str::FromStr};
+
+use reddd_macros::ValueType;
+
+use super::ValueError;
+use crate::domain::ValueType;
+
+const EMAIL_LOCAL_SPECIAL_CHARS: &str = "!#$%&'*+-/=?^_`{|}~.";
+const PHONE_NUMBER_SEPARATORS: &[char] = &[' ', '-', '.', '(', ')'];
+
+macro_rules! impl_text_value {
+    ($type:ident) => {
+        impl $type {
+            /// Gets the value as a string slice.
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl TryFrom<&str> for $type {
+            type Error = ValueError;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                Self::try_from(value.to_string())
+            }
+        }
+
+        impl FromStr for $type {
+            type Err = ValueError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::try_from(s)
+            }
+        }
+
+        impl From<$type> for String {
+            fn from(value: $type) -> Self {
+                value.as_str().into()
+            }
+        }
+
+        impl AsRef<str> for $type {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Eq for $type {}
+
+        impl Hash for $type {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state);
+            }
+        }
+    };
+}
+
+/// A string that is neither empty nor made of whitespace only (e.g. names
+/// and titles).
+#[derive(Clone, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct NonEmptyString(String);
+
+impl TryFrom<String> for NonEmptyString {
+    type Error = ValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().is_empty() {
+            | true => Err(ValueError::Empty),
+            | false => Ok(Self(value)),
+        }
+    }
+}
+
+impl_text_value!(NonEmptyString);
+
+/// An email address, which domain is normalized to lowercase.
+///
+/// Only the commonly used subset of RFC 5322 addresses is accepted, which
+/// excludes quoted local parts, comments and IP address literals.
+#[derive(Clone, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct EmailAddress(String);
+
+impl EmailAddress {
+    /// Gets the part of the address before the `@`.
+    pub fn local_part(&self) -> &str {
+        self.0
+            .rsplit_once('@')
+            .map(|(local, _)| local)
+            .unwrap_or_default()
+    }
+
+    /// Gets the part of the address after the `@`.
+    pub fn domain(&self) -> &str {
+        self.0
+            .rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for EmailAddress {
+    type Error = ValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        const KIND: &str = "email address";
+
+        if value.is_empty() {
+            return Err(ValueError::Empty);
+        }
+
+        if value.len() > 254 {
+            return Err(ValueError::invalid(KIND, "too long"));
+        }
+
+        let (local, domain) = value
+            .rsplit_once('@')
+            .ok_or_else(|| ValueError::invalid(KIND, "missing `@`"))?;
+
+        if local.is_empty() || local.len() > 64 {
+            return Err(ValueError::invalid(KIND, "invalid local part length"));
+        }
+
+        if local.starts_with('.')
+            || local.ends_with('.')
+            || local.contains("..")
+        {
+            return Err(ValueError::invalid(KIND, "misplaced `.`"));
+        }
+
+        if let Some(c) = local.chars().find(|c| {
+            !c.is_ascii_alphanumeric()
+                && !EMAIL_LOCAL_SPECIAL_CHARS.contains(*c)
+        }) {
+            return Err(ValueError::invalid(KIND, format!("invalid `{c}`")));
+        }
+
+        validate_domain(domain)
+            .map_err(|reason| ValueError::invalid(KIND, reason))?;
+
+        Ok(Self(format!("{local}@{}", domain.to_ascii_lowercase())))
+    }
+}
+
+impl_text_value!(EmailAddress);
+
+/// A phone number in the international E.164 format (e.g. `+14155552671`).
+///
+/// Spaces, dashes, dots and parentheses are accepted as separators, and are
+/// removed on construction.
+#[derive(Clone, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct PhoneNumber(String);
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = ValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        const KIND: &str = "phone number";
+
+        let value = value.replace(PHONE_NUMBER_SEPARATORS, "");
+
+        let digits = match value.strip_prefix('+') {
+            | Some(digits) => digits,
+            | None if value.is_empty() => return Err(ValueError::Empty),
+            | None => {
+                return Err(ValueError::invalid(KIND, "missing `+` prefix"));
+            }
+        };
+
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ValueError::invalid(KIND, "non-digit characters"));
+        }
+
+        if !(7..=15).contains(&digits.len()) {
+            return Err(ValueError::invalid(KIND, "invalid number of digits"));
+        }
+
+        if digits.starts_with('0') {
+            return Err(ValueError::invalid(KIND, "invalid country code"));
+        }
+
+        Ok(Self(value))
+    }
+}
+
+impl_text_value!(PhoneNumber);
+
+/// An absolute URL (e.g. `https://example.com/path`).
+#[derive(Clone, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Url(url::Url);
+
+impl TryFrom<String> for Url {
+    type Error = ValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Err(ValueError::Empty);
+        }
+
+        url::Url::parse(&value)
+            .map(Self)
+            .map_err(|err| ValueError::invalid("url", err))
+    }
+}
+
+impl From<url::Url> for Url {
+    fn from(value: url::Url) -> Self {
+        Self(value)
+    }
+}
+
+impl_text_value!(Url);
+
+fn validate_domain(domain: &str) -> Result<(), &'static str> {
+    let labels: Vec<_> = domain.split('.').collect();
+
+    if labels.len() < 2 {
+        return Err("domain must have a top-level domain");
+    }
+
+    for label in &labels {
+        if label.is_empty() || label.len() > 63 {
+            return Err("invalid domain label length");
+        }
+
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err("misplaced `-` in domain");
+        }
+
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err("invalid characters in domain");
+        }
+    }
+
+    match labels.last().unwrap().chars().all(|c| c.is_ascii_digit()) {
+        | true => Err("numeric top-level domain"),
+        | false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_empty_string_test() {
+        let value = NonEmptyString::try_from(" John ").unwrap();
+
+        assert_eq!(" John ", value.as_str());
+        assert_eq!(" John ", value.to_string());
+        assert_eq!(Err(ValueError::Empty), NonEmptyString::try_from(""));
+        assert_eq!(Err(ValueError::Empty), " \t".parse::<NonEmptyString>());
+    }
+
+    #[test]
+    fn email_address_test() {
+        let email: EmailAddress = "John.Doe+tag@Example.COM".parse().unwrap();
+
+        assert_eq!("John.Doe+tag@example.com", email.as_str());
+        assert_eq!("John.Doe+tag", email.local_part());
+        assert_eq!("example.com", email.domain());
+
+        for invalid in [
+            "john",
+            "@example.com",
+            "john@",
+            "john@localhost",
+            "john..doe@example.com",
+            ".john@example.com",
+            "john doe@example.com",
+            "john@-example.com",
+            "john@example..com",
+            "john@127.0.0.1",
+        ] {
+            assert!(
+                matches!(
+                    EmailAddress::try_from(invalid),
+                    Err(ValueError::Invalid { .. })
+                ),
+                "{invalid} must be invalid"
+            );
+        }
+
+        assert_eq!(Err(ValueError::Empty), EmailAddress::try_from(""));
+    }
+
+    #[test]
+    fn phone_number_test() {
+        let phone: PhoneNumber = "+1 (415) 555-2671".parse().unwrap();
+
+        assert_eq!("+14155552671", phone.as_str());
+        assert_eq!(phone, "+14155552671".parse().unwrap());
+
+        for invalid in ["4155552671", "+1 415 555 267a", "+123", "+0123456789"]
+        {
+            assert!(
+                matches!(
+                    PhoneNumber::try_from(invalid),
+                    Err(ValueError::Invalid { .. })
+                ),
+                "{invalid} must be invalid"
+            );
+        }
+
+        assert_eq!(Err(ValueError::Empty), PhoneNumber::try_from(" "));
+    }
+
+    #[test]
+    fn url_test() {
+        let url: Url = "https://example.com/path?q=1".parse().unwrap();
+
+        assert_eq!("https://example.com/path?q=1", url.as_str());
+        assert_eq!(Some("example.com"), url.as_inner().host_str());
+        assert!(matches!(
+            Url::try_from("example.com"),
+            Err(ValueError::Invalid { kind: "url", .. })
+        ));
+        assert_eq!(Err(ValueError::Empty), Url::try_from(""));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let email: EmailAddress =
+            serde_json::from_str(r#""john@Example.com""#).unwrap();
+
+        assert_eq!(
+            r#""john@example.com""#,
+            serde_json::to_string(&email).unwrap()
+        );
+        assert!(serde_json::from_str::<EmailAddress>(r#""john""#).is_err());
+        assert!(serde_json::from_str::<NonEmptyString>(r#""""#).is_err());
+        assert!(serde_json::from_str::<PhoneNumber>(r#""123""#).is_err());
+        assert!(serde_json::from_str::<Url>(r#""/relative""#).is_err());
+    }
+}
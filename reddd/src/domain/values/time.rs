@@ -0,0 +1,394 @@
+//! Time-bounded value types, built on [`DateTime<Utc>`].
+//!
+//! All ranges are half-open, which means that they include their start, but
+//! not their end, so consecutive ranges (e.g. monthly subscription periods)
+//! never overlap.
+
+use std::hash::Hash;
+
+use chrono::{DateTime, Duration, Utc};
+use reddd_macros::ValueType;
+
+use super::ValueError;
+use crate::domain::ValueType;
+
+/// A range of time between a start and an end (e.g. booking periods), where
+/// the start is never after the end.
+///
+/// # Example
+///
+/// ```ignore
+/// let booking = DateRange::new(check_in, check_out)?;
+///
+/// if bookings.iter().any(|b| b.period.overlaps(&booking)) {
+///     return Err(BookingError::Unavailable);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "RawRange<DateTime<Utc>>",
+        into = "RawRange<DateTime<Utc>>"
+    )
+)]
+pub struct DateRange((DateTime<Utc>, DateTime<Utc>));
+
+impl DateRange {
+    /// Creates a new range of time.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range, which is included in it.
+    /// * `end` - The end of the range, which is excluded from it.
+    pub fn new(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Self, ValueError> {
+        match start <= end {
+            | true => Ok(Self((start, end))),
+            | false => {
+                Err(ValueError::invalid("date range", "end is before start"))
+            }
+        }
+    }
+
+    /// Creates a new range of time that lasts for `duration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range, which is included in it.
+    /// * `duration` - The duration of the range.
+    pub fn starting_at(
+        start: DateTime<Utc>,
+        duration: Duration,
+    ) -> Result<Self, ValueError> {
+        let end = start.checked_add_signed(duration).ok_or_else(|| {
+            ValueError::invalid("date range", "end is out of range")
+        })?;
+
+        Self::new(start, end)
+    }
+
+    /// Gets the start of the range.
+    pub fn start(&self) -> &DateTime<Utc> {
+        &self.0 .0
+    }
+
+    /// Gets the end of the range.
+    pub fn end(&self) -> &DateTime<Utc> {
+        &self.0 .1
+    }
+
+    /// Gets the duration of the range.
+    pub fn duration(&self) -> Duration {
+        self.0 .1 - self.0 .0
+    }
+
+    /// Checks whether the range has a zero duration, in which case it
+    /// contains no instants.
+    pub fn is_empty(&self) -> bool {
+        self.0 .0 == self.0 .1
+    }
+
+    /// Checks whether `instant` is within the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `instant` - The instant to check.
+    pub fn contains(&self, instant: &DateTime<Utc>) -> bool {
+        self.start() <= instant && instant < self.end()
+    }
+
+    /// Checks whether `other` is entirely within the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The range to check.
+    pub fn contains_range(&self, other: &DateRange) -> bool {
+        self.start() <= other.start() && other.end() <= self.end()
+    }
+
+    /// Checks whether the range shares any instants with `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The range to check.
+    pub fn overlaps(&self, other: &DateRange) -> bool {
+        OpenDateRange::from(*self).overlaps(&OpenDateRange::from(*other))
+    }
+
+    /// Gets the range of the instants shared with `other`, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The range to intersect with.
+    pub fn intersection(&self, other: &DateRange) -> Option<DateRange> {
+        self.overlaps(other).then(|| {
+            Self((
+                *self.start().max(other.start()),
+                *self.end().min(other.end()),
+            ))
+        })
+    }
+}
+
+/// A range of time that has a start, and may have no end yet (e.g.
+/// subscriptions and employments).
+#[derive(Clone, Copy, Debug, ValueType)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "RawRange<Option<DateTime<Utc>>>",
+        into = "RawRange<Option<DateTime<Utc>>>"
+    )
+)]
+pub struct OpenDateRange((DateTime<Utc>, Option<DateTime<Utc>>));
+
+impl OpenDateRange {
+    /// Creates a new range of time.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range, which is included in it.
+    /// * `end` - The end of the range if any, which is excluded from it.
+    pub fn new(
+        start: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Self, ValueError> {
+        match end {
+            | Some(end) => DateRange::new(start, end).map(Self::from),
+            | None => Ok(Self((start, None))),
+        }
+    }
+
+    /// Creates a new range of time that has no end.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range, which is included in it.
+    pub fn since(start: DateTime<Utc>) -> Self {
+        Self((start, None))
+    }
+
+    /// Gets the start of the range.
+    pub fn start(&self) -> &DateTime<Utc> {
+        &self.0 .0
+    }
+
+    /// Gets the end of the range, if any.
+    pub fn end(&self) -> Option<&DateTime<Utc>> {
+        self.0 .1.as_ref()
+    }
+
+    /// Checks whether the range has no end.
+    pub fn is_open(&self) -> bool {
+        self.0 .1.is_none()
+    }
+
+    /// Checks whether the range has a zero duration, in which case it
+    /// contains no instants.
+    pub fn is_empty(&self) -> bool {
+        self.end() == Some(self.start())
+    }
+
+    /// Checks whether `instant` is within the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `instant` - The instant to check.
+    pub fn contains(&self, instant: &DateTime<Utc>) -> bool {
+        self.start() <= instant && self.end().is_none_or(|end| instant < end)
+    }
+
+    /// Checks whether the range shares any instants with `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The range to check.
+    pub fn overlaps(&self, other: &OpenDateRange) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.end().is_none_or(|end| other.start() < end)
+            && other.end().is_none_or(|end| self.start() < end)
+    }
+
+    /// Ends the range at `end`, making it a bounded one.
+    ///
+    /// # Arguments
+    ///
+    /// * `end` - The end of the range, which is excluded from it.
+    pub fn close(&self, end: DateTime<Utc>) -> Result<DateRange, ValueError> {
+        DateRange::new(*self.start(), end)
+    }
+}
+
+impl From<DateRange> for OpenDateRange {
+    fn from(value: DateRange) -> Self {
+        Self((*value.start(), Some(*value.end())))
+    }
+}
+
+impl TryFrom<OpenDateRange> for DateRange {
+    type Error = ValueError;
+
+    fn try_from(value: OpenDateRange) -> Result<Self, Self::Error> {
+        match value.end() {
+            | Some(end) => Self::new(*value.start(), *end),
+            | None => Err(ValueError::invalid("date range", "missing end")),
+        }
+    }
+}
+
+impl Eq for DateRange {}
+
+impl Hash for DateRange {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Eq for OpenDateRange {}
+
+impl Hash for OpenDateRange {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// A serialization form of ranges, which are validated when deserialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RawRange<E> {
+    start: DateTime<Utc>,
+    end: E,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRange<DateTime<Utc>>> for DateRange {
+    type Error = ValueError;
+
+    fn try_from(value: RawRange<DateTime<Utc>>) -> Result<Self, Self::Error> {
+        Self::new(value.start, value.end)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<DateRange> for RawRange<DateTime<Utc>> {
+    fn from(value: DateRange) -> Self {
+        Self {
+            start: *value.start(),
+            end: *value.end(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRange<Option<DateTime<Utc>>>> for OpenDateRange {
+    type Error = ValueError;
+
+    fn try_from(
+        value: RawRange<Option<DateTime<Utc>>>,
+    ) -> Result<Self, Self::Error> {
+        Self::new(value.start, value.end)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<OpenDateRange> for RawRange<Option<DateTime<Utc>>> {
+    fn from(value: OpenDateRange) -> Self {
+        Self {
+            start: *value.start(),
+            end: value.end().copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap()
+    }
+
+    fn range(start: u32, end: u32) -> DateRange {
+        DateRange::new(day(start), day(end)).unwrap()
+    }
+
+    #[test]
+    fn date_range_test() {
+        let r = range(1, 10);
+
+        assert_eq!(Duration::days(9), r.duration());
+        assert_eq!(Ok(r), DateRange::starting_at(day(1), Duration::days(9)));
+        assert!(DateRange::new(day(2), day(1)).is_err());
+        assert!(range(1, 1).is_empty());
+
+        assert!(r.contains(&day(1)));
+        assert!(!r.contains(&day(10)));
+        assert!(r.contains_range(&range(2, 10)));
+        assert!(!r.contains_range(&range(2, 11)));
+
+        assert!(r.overlaps(&range(9, 12)));
+        assert!(!r.overlaps(&range(10, 12)));
+        assert!(!r.overlaps(&range(5, 5)));
+        assert_eq!(Some(range(5, 10)), r.intersection(&range(5, 20)));
+        assert_eq!(None, r.intersection(&range(10, 20)));
+        assert_eq!(&(day(1), day(10)), r.as_inner());
+    }
+
+    #[test]
+    fn open_date_range_test() {
+        let open = OpenDateRange::since(day(5));
+
+        assert!(open.is_open());
+        assert!(open.contains(&day(30)));
+        assert!(!open.contains(&day(4)));
+        assert!(open.overlaps(&range(1, 6).into()));
+        assert!(!open.overlaps(&range(1, 5).into()));
+        assert!(open.overlaps(&OpenDateRange::since(day(1))));
+        assert!(!open.overlaps(&range(6, 6).into()));
+        assert!(OpenDateRange::new(day(5), Some(day(4))).is_err());
+
+        let closed = open.close(day(10)).unwrap();
+
+        assert_eq!(range(5, 10), closed);
+        assert_eq!(
+            Ok(closed),
+            DateRange::try_from(OpenDateRange::from(closed))
+        );
+        assert!(DateRange::try_from(open).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let serialized = serde_json::to_value(range(1, 2)).unwrap();
+
+        assert_eq!(
+            serde_json::json!({
+                "start": "2024-01-01T00:00:00Z",
+                "end": "2024-01-02T00:00:00Z",
+            }),
+            serialized
+        );
+        assert_eq!(range(1, 2), serde_json::from_value(serialized).unwrap());
+        assert!(serde_json::from_value::<DateRange>(serde_json::json!({
+            "start": "2024-01-02T00:00:00Z",
+            "end": "2024-01-01T00:00:00Z",
+        }))
+        .is_err());
+
+        let open: OpenDateRange = serde_json::from_value(
+            serde_json::json!({ "start": "2024-01-01T00:00:00Z", "end": null }),
+        )
+        .unwrap();
+
+        assert_eq!(OpenDateRange::since(day(1)), open);
+    }
+}